@@ -1,19 +1,19 @@
 use libipld::cache::Cache;
-use std::marker::PhantomData;
 use std::path::PathBuf;
-use std::sync::Arc;
-use substrate_subxt as subxt;
+use std::time::Duration;
 use sunshine_bounty_gbot::GBot;
+use sunshine_client::body::BountyBody;
+use sunshine_client::client::codec::Cid;
 use sunshine_client::client::{Client as _, Result};
-use sunshine_client::{
-    bounty::{
-        Bounty, BountyEventsDecoder, BountyPaymentExecutedEvent, BountyPostedEvent,
-        BountyRaiseContributionEvent, BountySubmissionPostedEvent,
-    },
-    GithubIssue,
-};
-use sunshine_client::{Client, Runtime};
-use tokio::task;
+use sunshine_client::escrow::EscrowClient;
+use sunshine_client::events::{EventClient, SunshineEvent};
+use sunshine_client::{Client, GithubIssue};
+use tokio::time::delay_for;
+
+/// Delay before retrying a block that failed to be fetched, doubled on every
+/// failure up to `MAX_BACKOFF`.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -21,113 +21,55 @@ async fn main() -> Result<()> {
     let github = GBot::new()?;
     let root = dirs::config_dir().unwrap().join("sunshine-bounty-bot");
     let chain_spec: PathBuf = Default::default();
-    let client = Arc::new(Client::new(&root, &chain_spec).await?);
-
-    let post =
-        Subscription::<_, BountyPostedEvent<Runtime>>::subscribe(client.chain_client()).await?;
-    let contrib =
-        Subscription::<_, BountyRaiseContributionEvent<Runtime>>::subscribe(client.chain_client())
-            .await?;
-    let submit =
-        Subscription::<_, BountySubmissionPostedEvent<Runtime>>::subscribe(client.chain_client())
-            .await?;
-    let approval =
-        Subscription::<_, BountyPaymentExecutedEvent<Runtime>>::subscribe(client.chain_client())
-            .await?;
-
-    let post = task::spawn(process_subscription(client.clone(), github.clone(), post));
-    let contrib = task::spawn(process_subscription(
-        client.clone(),
-        github.clone(),
-        contrib,
-    ));
-    let submit = task::spawn(process_subscription(client.clone(), github.clone(), submit));
-    let approval = task::spawn(process_subscription(client, github, approval));
-
-    post.await?;
-    contrib.await?;
-    submit.await?;
-    approval.await?;
-
-    Ok(())
-}
-
-pub struct Subscription<R: subxt::Runtime, E: subxt::Event<R>> {
-    _marker: PhantomData<E>,
-    subscription: subxt::EventSubscription<R>,
-}
-
-impl<R: subxt::Runtime + Bounty, E: subxt::Event<R>> Subscription<R, E> {
-    async fn subscribe(client: &subxt::Client<R>) -> Result<Self> {
-        let sub = client.subscribe_events().await?;
-        let mut decoder = subxt::EventsDecoder::<R>::new(client.metadata().clone());
-        decoder.with_bounty();
-        let mut subscription = subxt::EventSubscription::<R>::new(sub, decoder);
-        subscription.filter_event::<E>();
-        Ok(Self {
-            _marker: PhantomData,
-            subscription,
-        })
-    }
-
-    async fn next(&mut self) -> Option<Result<E>> {
-        match self.subscription.next().await {
-            Some(Ok(raw)) => Some(E::decode(&mut &raw.data[..]).map_err(Into::into)),
-            Some(Err(err)) => Some(Err(err.into())),
-            None => None,
-        }
-    }
-}
+    let client = Client::new(&root, &chain_spec).await?;
 
-async fn process_subscription<E: subxt::Event<Runtime> + Into<Event>>(
-    client: Arc<Client>,
-    github: GBot,
-    mut subscription: Subscription<Runtime, E>,
-) {
-    while let Some(res) = subscription.next().await {
-        if let Err(err) = process_event(&client, &github, res.map(Into::into)).await {
+    // Only finalized events are commented on, so comments aren't left for
+    // events of blocks that get reverted.
+    let mut events = client.subscribe_sunshine_events(true).await?;
+    client.pin_open_escrows().await?;
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        let record = match events.next().await {
+            Ok(record) => {
+                backoff = MIN_BACKOFF;
+                record
+            }
+            Err(err) => {
+                // The block is retried by the next call, wait for the node
+                // to recover instead of hammering it.
+                log::error!(
+                    "failed to fetch events, retrying in {:?}: {:?}",
+                    backoff,
+                    err
+                );
+                delay_for(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                continue;
+            }
+        };
+        if let Err(err) = process_event(&client, &github, record.event).await {
             log::error!("{:?}", err);
         }
+        log::debug!("{:?}", client.offchain_client().cache_stats());
     }
 }
 
-pub enum Event {
-    BountyPosted(BountyPostedEvent<Runtime>),
-    RaiseContribution(BountyRaiseContributionEvent<Runtime>),
-    SubmissionPosted(BountySubmissionPostedEvent<Runtime>),
-    PaymentExecuted(BountyPaymentExecutedEvent<Runtime>),
-}
-
-impl From<BountyPostedEvent<Runtime>> for Event {
-    fn from(ev: BountyPostedEvent<Runtime>) -> Self {
-        Self::BountyPosted(ev)
-    }
-}
-
-impl From<BountyRaiseContributionEvent<Runtime>> for Event {
-    fn from(ev: BountyRaiseContributionEvent<Runtime>) -> Self {
-        Self::RaiseContribution(ev)
-    }
-}
-
-impl From<BountySubmissionPostedEvent<Runtime>> for Event {
-    fn from(ev: BountySubmissionPostedEvent<Runtime>) -> Self {
-        Self::SubmissionPosted(ev)
-    }
+/// Fetches the body of a bounty or submission. Only bounties and submissions
+/// of github issues are commented on.
+async fn github_issue(client: &Client, cid: &Cid) -> Result<Option<GithubIssue>> {
+    let body: BountyBody = client.offchain_client().get(cid).await?;
+    Ok(body.github_issue().cloned())
 }
 
-impl From<BountyPaymentExecutedEvent<Runtime>> for Event {
-    fn from(ev: BountyPaymentExecutedEvent<Runtime>) -> Self {
-        Self::PaymentExecuted(ev)
-    }
-}
-
-async fn process_event(client: &Client, github: &GBot, event: Result<Event>) -> Result<()> {
-    match event? {
-        Event::BountyPosted(event) => {
+async fn process_event(client: &Client, github: &GBot, event: SunshineEvent) -> Result<()> {
+    match event {
+        SunshineEvent::BountyPosted(event) => {
             // fetch structured data from client
             let event_cid = event.description;
-            let bounty_body: GithubIssue = client.offchain_client().get(&event_cid).await?;
+            let bounty_body = match github_issue(client, &event_cid).await? {
+                Some(issue) => issue,
+                None => return Ok(()),
+            };
             // new issue comment
             github
                 .new_bounty_issue(
@@ -139,10 +81,13 @@ async fn process_event(client: &Client, github: &GBot, event: Result<Event>) ->
                 )
                 .await?;
         }
-        Event::RaiseContribution(event) => {
+        SunshineEvent::BountyRaiseContribution(event) => {
             // fetch structured data from client
             let event_cid = event.bounty_ref;
-            let bounty_body: GithubIssue = client.offchain_client().get(&event_cid).await?;
+            let bounty_body = match github_issue(client, &event_cid).await? {
+                Some(issue) => issue,
+                None => return Ok(()),
+            };
             // update existing bounty comment
             github
                 .update_bounty_issue(
@@ -154,13 +99,18 @@ async fn process_event(client: &Client, github: &GBot, event: Result<Event>) ->
                 )
                 .await?;
         }
-        Event::SubmissionPosted(event) => {
+        SunshineEvent::BountySubmissionPosted(event) => {
             // fetch structured data from client
             let bounty_event_cid = event.bounty_ref;
             let submission_event_cid = event.submission_ref;
-            let bounty_body: GithubIssue = client.offchain_client().get(&bounty_event_cid).await?;
-            let submission_body: GithubIssue =
-                client.offchain_client().get(&submission_event_cid).await?;
+            let bounty_body = match github_issue(client, &bounty_event_cid).await? {
+                Some(issue) => issue,
+                None => return Ok(()),
+            };
+            let submission_body = match github_issue(client, &submission_event_cid).await? {
+                Some(issue) => issue,
+                None => return Ok(()),
+            };
             // new issue comment
             github
                 .new_submission_issue(
@@ -176,13 +126,18 @@ async fn process_event(client: &Client, github: &GBot, event: Result<Event>) ->
                 )
                 .await?;
         }
-        Event::PaymentExecuted(event) => {
+        SunshineEvent::BountyPaymentExecuted(event) => {
             // fetch structured data from client
             let bounty_event_cid = event.bounty_ref;
             let submission_event_cid = event.submission_ref;
-            let bounty_body: GithubIssue = client.offchain_client().get(&bounty_event_cid).await?;
-            let submission_body: GithubIssue =
-                client.offchain_client().get(&submission_event_cid).await?;
+            let bounty_body = match github_issue(client, &bounty_event_cid).await? {
+                Some(issue) => issue,
+                None => return Ok(()),
+            };
+            let submission_body = match github_issue(client, &submission_event_cid).await? {
+                Some(issue) => issue,
+                None => return Ok(()),
+            };
             // update existing submission comment
             github
                 .approve_submission_issue(
@@ -208,6 +163,15 @@ async fn process_event(client: &Client, github: &GBot, event: Result<Event>) ->
                 )
                 .await?;
         }
+        // keep the descriptions of open bounties from being garbage collected
+        SunshineEvent::EscrowPosted(_)
+        | SunshineEvent::MilestoneSubmitted(_)
+        | SunshineEvent::EscrowCompleted(_)
+        | SunshineEvent::EscrowRefunded(_)
+        | SunshineEvent::EscrowExpired(_) => {
+            client.pin_open_escrows().await?;
+        }
+        _ => {}
     }
     Ok(())
 }
@@ -1,8 +1,8 @@
+use crate::{escrow, faucet, fee, indices, proxy, vesting};
 use clap::Clap;
 use std::path::PathBuf;
 use sunshine_bounty_cli::bounty;
-use sunshine_faucet_cli::MintCommand;
-use sunshine_identity_cli::{account, device, id, key, wallet};
+use sunshine_identity_cli::{account, device, id, key};
 
 #[derive(Clone, Debug, Clap)]
 pub struct Opts {
@@ -22,6 +22,7 @@ pub enum SubCommand {
     Id(IdCommand),
     Wallet(WalletCommand),
     Bounty(BountyCommand),
+    Proxy(ProxyCommand),
     Run,
 }
 
@@ -48,7 +49,8 @@ pub struct AccountCommand {
 pub enum AccountSubCommand {
     Create(account::AccountCreateCommand),
     Password(account::AccountPasswordCommand),
-    Mint(MintCommand),
+    Mint(faucet::MintCommand),
+    MintStatus(faucet::MintStatusCommand),
 }
 
 #[derive(Clone, Debug, Clap)]
@@ -86,8 +88,26 @@ pub struct WalletCommand {
 
 #[derive(Clone, Debug, Clap)]
 pub enum WalletSubCommand {
-    Balance(wallet::WalletBalanceCommand),
-    Transfer(wallet::WalletTransferCommand),
+    Balance(vesting::WalletBalanceCommand),
+    Vest(vesting::WalletVestCommand),
+    Transfer(indices::WalletTransferCommand),
+    EstimateFee(fee::WalletEstimateFeeCommand),
+    ClaimIndex(indices::WalletClaimIndexCommand),
+    FreeIndex(indices::WalletFreeIndexCommand),
+    GetIndex(indices::WalletGetIndexCommand),
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ProxyCommand {
+    #[clap(subcommand)]
+    pub cmd: ProxySubCommand,
+}
+
+#[derive(Clone, Debug, Clap)]
+pub enum ProxySubCommand {
+    Add(proxy::ProxyAddCommand),
+    Remove(proxy::ProxyRemoveCommand),
+    List(proxy::ProxyListCommand),
 }
 
 #[derive(Clone, Debug, Clap)]
@@ -107,4 +127,46 @@ pub enum BountySubCommand {
     GetSubmission(bounty::GetSubmissionCommand),
     GetOpenBounties(bounty::GetOpenBountiesCommand),
     GetOpenSubmissions(bounty::GetOpenSubmissionsCommand),
+    Escrow(EscrowCommand),
+    Dispute(DisputeCommand),
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowCommand {
+    #[clap(subcommand)]
+    pub cmd: EscrowSubCommand,
+}
+
+#[derive(Clone, Debug, Clap)]
+pub enum EscrowSubCommand {
+    Post(escrow::EscrowPostCommand),
+    Contribute(escrow::EscrowContributeCommand),
+    Submit(escrow::EscrowSubmitCommand),
+    Approve(escrow::EscrowApproveCommand),
+    Reject(escrow::EscrowRejectCommand),
+    SetApprovers(escrow::EscrowSetApproversCommand),
+    Cosign(escrow::EscrowCosignCommand),
+    SetVesting(escrow::EscrowSetVestingCommand),
+    // storage helpers
+    Get(escrow::GetEscrowCommand),
+    GetSubmission(escrow::GetEscrowSubmissionCommand),
+    // runtime api helpers
+    List(escrow::ListEscrowsCommand),
+    Submissions(escrow::ListEscrowSubmissionsCommand),
+    Contributions(escrow::ListEscrowContributionsCommand),
+    Pending(escrow::ListPendingApprovalsCommand),
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct DisputeCommand {
+    #[clap(subcommand)]
+    pub cmd: DisputeSubCommand,
+}
+
+#[derive(Clone, Debug, Clap)]
+pub enum DisputeSubCommand {
+    Open(escrow::DisputeOpenCommand),
+    Vote(escrow::DisputeVoteCommand),
+    // storage helpers
+    Get(escrow::GetDisputeCommand),
 }
@@ -0,0 +1,538 @@
+use crate::fee::InvalidAccountId;
+use clap::Clap;
+use std::path::Path;
+use sunshine_cli_utils::{Client as _, Result};
+use sunshine_client::body::{Attachment, BountyBody, GiteaIssue, GitlabMergeRequest, MarkdownSpec};
+use sunshine_client::client::codec::Cid;
+use sunshine_client::escrow::EscrowClient;
+use sunshine_client::identity::resolve;
+use sunshine_client::{AccountId, Client, GithubIssue, Uid};
+use thiserror::Error;
+
+/// Number of blocks produced per day at six seconds per block.
+const DAYS: u32 = 24 * 60 * 10;
+
+#[derive(Debug, Error)]
+#[error("Expected a github, gitlab or gitea url, or the path of a markdown spec.")]
+pub struct InvalidBody;
+
+#[derive(Debug, Error)]
+#[error("Expected an attachment like <name>=<cid>.")]
+pub struct InvalidAttachment;
+
+/// Parses the body of a bounty or submission. Urls are parsed as github
+/// issues, gitlab merge requests or gitea issues, anything else is read as
+/// the path of a markdown spec with `attachments`.
+fn parse_body(body: &str, attachments: &[String]) -> Result<BountyBody> {
+    let url = match body.strip_prefix("https://") {
+        Some(url) => url.trim_end_matches('/'),
+        None => return read_spec(body, attachments),
+    };
+    let parts = url.split('/').collect::<Vec<_>>();
+    let number = |number: &str| number.parse().map_err(|_| InvalidBody);
+    Ok(match parts.as_slice() {
+        ["github.com", owner, repo, "issues", n] => BountyBody::GithubIssue(GithubIssue {
+            issue_number: number(n)?,
+            repo_owner: owner.to_string(),
+            repo_name: repo.to_string(),
+        }),
+        [host, project @ .., "-", "merge_requests", n] if !project.is_empty() => {
+            BountyBody::GitlabMergeRequest(GitlabMergeRequest {
+                host: host.to_string(),
+                project: project.join("/"),
+                iid: number(n)?,
+            })
+        }
+        [host, owner, repo, "issues", n] => BountyBody::GiteaIssue(GiteaIssue {
+            host: host.to_string(),
+            repo_owner: owner.to_string(),
+            repo_name: repo.to_string(),
+            issue_number: number(n)?,
+        }),
+        _ => return Err(InvalidBody.into()),
+    })
+}
+
+/// Reads a markdown spec. The title is the first heading or the file name.
+fn read_spec(path: &str, attachments: &[String]) -> Result<BountyBody> {
+    let path = Path::new(path);
+    let text = std::fs::read_to_string(path).map_err(|_| InvalidBody)?;
+    let title = text
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .ok_or(InvalidBody)?;
+    let attachments = attachments
+        .iter()
+        .map(|attachment| parse_attachment(attachment))
+        .collect::<Result<_>>()?;
+    Ok(BountyBody::MarkdownSpec(MarkdownSpec {
+        title,
+        text,
+        attachments,
+    }))
+}
+
+fn parse_attachment(attachment: &str) -> Result<Attachment> {
+    match attachment.splitn(2, '=').collect::<Vec<_>>().as_slice() {
+        [name, cid] => Ok(Attachment {
+            name: name.to_string(),
+            cid: cid.parse().map_err(|_| InvalidAttachment)?,
+        }),
+        _ => Err(InvalidAttachment.into()),
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowPostCommand {
+    /// Url of the issue or merge request, or path of a markdown spec.
+    pub body: String,
+    #[clap(long = "deposit")]
+    pub deposit: u128,
+    /// Days until unmet milestones are refunded to the contributors.
+    #[clap(long = "days", default_value = "30")]
+    pub days: u32,
+    /// Attachments of a markdown spec as `<name>=<cid>`.
+    #[clap(long = "attach")]
+    pub attachments: Vec<String>,
+    /// Payout of each milestone in order.
+    #[clap(required = true)]
+    pub milestones: Vec<u128>,
+}
+
+impl EscrowPostCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let body = parse_body(&self.body, &self.attachments)?;
+        let event = client
+            .post_escrow(
+                body,
+                self.milestones.clone(),
+                self.deposit,
+                self.days.saturating_mul(DAYS),
+            )
+            .await?;
+        println!(
+            "Posted escrow bounty {} with a deposit of {}",
+            event.id, event.deposit
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowContributeCommand {
+    pub id: u64,
+    pub amount: u128,
+}
+
+impl EscrowContributeCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client.contribute_to_escrow(self.id, self.amount).await?;
+        println!(
+            "Contributed {} to escrow bounty {}, which now holds {}",
+            event.amount, event.id, event.total
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowSubmitCommand {
+    pub id: u64,
+    pub milestone: u32,
+    /// Url of the issue or merge request, or path of a markdown spec.
+    pub body: String,
+    /// Attachments of a markdown spec as `<name>=<cid>`.
+    #[clap(long = "attach")]
+    pub attachments: Vec<String>,
+}
+
+impl EscrowSubmitCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let body = parse_body(&self.body, &self.attachments)?;
+        let event = client
+            .submit_milestone(self.id, self.milestone, body)
+            .await?;
+        println!(
+            "Submitted {} for milestone {} of escrow bounty {}",
+            event.submission_id, event.milestone, event.id
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowApproveCommand {
+    pub id: u64,
+    #[clap(required = true)]
+    pub submission_ids: Vec<u64>,
+    /// Approve all submissions in one extrinsic that fails as a whole if one
+    /// approval fails.
+    #[clap(long = "batch")]
+    pub batch: bool,
+    /// Account of the poster to approve on behalf of, which has to have added
+    /// the device key as a proxy.
+    #[clap(long = "proxy-for")]
+    pub proxy_for: Option<String>,
+}
+
+impl EscrowApproveCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let real: Option<AccountId> = if let Some(account) = &self.proxy_for {
+            Some(account.parse().map_err(|_| InvalidAccountId)?)
+        } else {
+            None
+        };
+        let events = if self.batch {
+            client
+                .approve_milestones(self.id, &self.submission_ids, real.as_ref())
+                .await?
+        } else {
+            let mut events = Vec::with_capacity(self.submission_ids.len());
+            for submission_id in &self.submission_ids {
+                if let Some(real) = &real {
+                    events.extend(
+                        client
+                            .approve_milestones(self.id, &[*submission_id], Some(real))
+                            .await?,
+                    );
+                } else {
+                    events.push(client.approve_milestone(self.id, *submission_id).await?);
+                }
+            }
+            events
+        };
+        for event in events {
+            println!(
+                "Paid {} to {} for milestone {} of escrow bounty {}",
+                event.amount,
+                event.submitter.to_string(),
+                event.milestone,
+                event.id
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowSetApproversCommand {
+    pub id: u64,
+    /// Number of approvals that pay out a submission.
+    pub threshold: u32,
+    /// Uids of the team approving submissions.
+    #[clap(required = true)]
+    pub uids: Vec<Uid>,
+}
+
+impl EscrowSetApproversCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client
+            .set_escrow_approvers(self.id, self.uids.clone(), self.threshold)
+            .await?;
+        println!(
+            "Submissions of escrow bounty {} need {} approvals of {:?}",
+            event.id, event.threshold, event.uids
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowCosignCommand {
+    pub id: u64,
+    pub submission_id: u64,
+}
+
+impl EscrowCosignCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let (approved, paid) = client.cosign_milestone(self.id, self.submission_id).await?;
+        println!(
+            "Uid {} approved submission {} of escrow bounty {} ({} approvals)",
+            approved.uid, approved.submission_id, approved.id, approved.approvals
+        );
+        if let Some(event) = paid {
+            println!(
+                "Paid {} to {} for milestone {} of escrow bounty {}",
+                event.amount,
+                event.submitter.to_string(),
+                event.milestone,
+                event.id
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowSetVestingCommand {
+    pub id: u64,
+    /// Days the payouts vest over.
+    pub days: u32,
+}
+
+impl EscrowSetVestingCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client
+            .set_escrow_vesting(self.id, self.days.saturating_mul(DAYS))
+            .await?;
+        println!(
+            "Payouts of escrow bounty {} vest over {} blocks",
+            event.id, event.period
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct EscrowRejectCommand {
+    pub id: u64,
+    pub submission_id: u64,
+}
+
+impl EscrowRejectCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client.reject_milestone(self.id, self.submission_id).await?;
+        println!(
+            "Rejected submission {} of escrow bounty {}",
+            event.submission_id, event.id
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct GetEscrowCommand {
+    pub id: u64,
+}
+
+impl GetEscrowCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        if let Some(bounty) = client.escrow(self.id).await? {
+            println!("poster: {}", bounty.poster.to_string());
+            println!("description: {}", bounty.description);
+            let target: u128 = bounty.milestones.iter().sum();
+            println!("funds: {}/{}", bounty.total, target);
+            println!("deadline: block {}", bounty.deadline);
+            if let Some(period) = client.escrow_vesting_period(self.id).await? {
+                println!("vesting: {} blocks", period);
+            }
+            if let Some(approvers) = client.escrow_approvers(self.id).await? {
+                println!("approvers: {} of {:?}", approvers.threshold, approvers.uids);
+            }
+            for (i, amount) in bounty.milestones.iter().enumerate() {
+                let status = if (i as u32) < bounty.next_milestone {
+                    "paid"
+                } else {
+                    "open"
+                };
+                println!("milestone {}: {} ({})", i, amount, status);
+            }
+        } else {
+            println!("No escrow bounty with id {}", self.id);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct GetEscrowSubmissionCommand {
+    pub id: u64,
+    pub submission_id: u64,
+}
+
+impl GetEscrowSubmissionCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        if let Some(submission) = client
+            .milestone_submission(self.id, self.submission_id)
+            .await?
+        {
+            println!("submitter: {}", submission.submitter.to_string());
+            println!("milestone: {}", submission.milestone);
+            println!("submission: {}", submission.submission);
+            println!("rejected: {}", submission.rejected);
+        } else {
+            println!(
+                "No submission with id {} for escrow bounty {}",
+                self.submission_id, self.id
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ListEscrowsCommand {
+    /// Minimum amount contributed to a listed bounty.
+    #[clap(long = "min-total", default_value = "0")]
+    pub min_total: u128,
+    #[clap(long = "offset", default_value = "0")]
+    pub offset: u32,
+    #[clap(long = "limit", default_value = "20")]
+    pub limit: u32,
+}
+
+impl ListEscrowsCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let bounties = client
+            .open_escrows(self.min_total, self.offset, self.limit)
+            .await?;
+        for (id, bounty) in bounties {
+            let target: u128 = bounty.milestones.iter().sum();
+            println!(
+                "{}: {} ({}/{}, milestone {}/{})",
+                id,
+                bounty.description,
+                bounty.total,
+                target,
+                bounty.next_milestone,
+                bounty.milestones.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ListEscrowSubmissionsCommand {
+    pub id: u64,
+}
+
+impl ListEscrowSubmissionsCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        for (submission_id, submission) in client.escrow_submissions(self.id).await? {
+            let status = if submission.rejected {
+                "rejected"
+            } else {
+                "pending"
+            };
+            println!(
+                "{}: milestone {} by {} ({})",
+                submission_id,
+                submission.milestone,
+                submission.submitter.to_string(),
+                status
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ListEscrowContributionsCommand {
+    /// Account to list the contributions of, defaults to the account of the
+    /// device key.
+    pub account: Option<String>,
+}
+
+impl ListEscrowContributionsCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let account: AccountId = if let Some(account) = &self.account {
+            account.parse().map_err(|_| InvalidAccountId)?
+        } else {
+            client.chain_signer()?.account_id().clone()
+        };
+        let contributions = client.escrow_contributions(&account).await?;
+        let total: u128 = contributions.iter().map(|(_, amount)| amount).sum();
+        for (id, amount) in contributions {
+            println!("{}: {}", id, amount);
+        }
+        println!("total: {}", total);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ListPendingApprovalsCommand {
+    /// Uid to list the pending approvals of, defaults to the uid of the
+    /// device key.
+    pub uid: Option<Uid>,
+}
+
+impl ListPendingApprovalsCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let uid = if let Some(uid) = self.uid {
+            uid
+        } else {
+            resolve(client, None).await?
+        };
+        for (id, submission_id, approvals) in client.pending_approvals(uid).await? {
+            println!(
+                "{}: submission {} approved by {:?}",
+                id, submission_id, approvals
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct DisputeOpenCommand {
+    pub id: u64,
+    pub submission_id: u64,
+    /// Cid of the evidence supporting the submission.
+    pub evidence: String,
+}
+
+impl DisputeOpenCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let evidence: Cid = self.evidence.parse()?;
+        let event = client
+            .open_dispute(self.id, self.submission_id, evidence)
+            .await?;
+        println!(
+            "Disputed submission {} of escrow bounty {} with a bond of {}",
+            event.submission_id, event.id, event.bond
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct DisputeVoteCommand {
+    pub id: u64,
+    pub submission_id: u64,
+    /// `aye` pays out the milestone, `nay` rejects the submission.
+    #[clap(possible_values = &["aye", "nay"])]
+    pub vote: String,
+}
+
+impl DisputeVoteCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client
+            .vote_dispute(self.id, self.submission_id, self.vote == "aye")
+            .await?;
+        println!(
+            "Voted {} on the dispute about submission {} of escrow bounty {}",
+            self.vote, event.submission_id, event.id
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct GetDisputeCommand {
+    pub id: u64,
+    pub submission_id: u64,
+}
+
+impl GetDisputeCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        if let Some(dispute) = client.dispute(self.id, self.submission_id).await? {
+            println!("bond: {}", dispute.bond);
+            println!("evidence: {}", dispute.evidence);
+            println!("ayes: {}", dispute.ayes.len());
+            println!("nays: {}", dispute.nays.len());
+        } else {
+            println!(
+                "No dispute about submission {} of escrow bounty {}",
+                self.submission_id, self.id
+            );
+        }
+        Ok(())
+    }
+}
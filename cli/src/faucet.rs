@@ -0,0 +1,59 @@
+use crate::fee::InvalidAccountId;
+use clap::Clap;
+use sunshine_cli_utils::{Client as _, Result};
+use sunshine_client::faucet::FaucetClient;
+use sunshine_client::{AccountId, Client};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("The faucet can't be used before block {0}.")]
+pub struct MintUnavailable(u32);
+
+#[derive(Clone, Debug, Clap)]
+pub struct MintCommand;
+
+impl MintCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let account = client.chain_signer()?.account_id().clone();
+        let status = client.mint_status(&account).await?;
+        if !status.can_mint() {
+            return Err(MintUnavailable(status.available_at()).into());
+        }
+        let event = client.mint().await?;
+        println!(
+            "minted {} tokens into your account {}",
+            event.amount,
+            event.account.to_string()
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct MintStatusCommand {
+    /// Account to check, defaults to the account of the device key.
+    pub account: Option<String>,
+}
+
+impl MintStatusCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let account: AccountId = if let Some(account) = &self.account {
+            account.parse().map_err(|_| InvalidAccountId)?
+        } else {
+            client.chain_signer()?.account_id().clone()
+        };
+        let status = client.mint_status(&account).await?;
+        println!("current block: {}", status.now);
+        println!("mints left this period: {}", status.mints_left);
+        if status.can_mint() {
+            println!("{} can mint now", account.to_string());
+        } else {
+            println!(
+                "{} can mint at block {}",
+                account.to_string(),
+                status.available_at()
+            );
+        }
+        Ok(())
+    }
+}
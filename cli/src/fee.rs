@@ -0,0 +1,31 @@
+use clap::Clap;
+use sunshine_cli_utils::Result;
+use sunshine_client::fee::FeeClient;
+use sunshine_client::indices::{IndicesClient, Recipient};
+use sunshine_client::Client;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Expected an ss58 or hex encoded account id.")]
+pub struct InvalidAccountId;
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletEstimateFeeCommand {
+    /// Account index, ss58 account id or @github handle the transfer is sent
+    /// to.
+    pub recipient: String,
+    pub amount: u128,
+}
+
+impl WalletEstimateFeeCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let recipient: Recipient = self.recipient.parse()?;
+        let to = client.lookup(&recipient).await?;
+        let fee = client.estimate_transfer_fee(&to, self.amount).await?;
+        println!(
+            "Transferring {} to {} costs a fee of {}",
+            self.amount, self.recipient, fee
+        );
+        Ok(())
+    }
+}
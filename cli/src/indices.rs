@@ -0,0 +1,66 @@
+use clap::Clap;
+use sunshine_cli_utils::Result;
+use sunshine_client::indices::{IndicesClient, Recipient};
+use sunshine_client::Client;
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletTransferCommand {
+    /// Account index, ss58 account id or @github handle to transfer to.
+    pub recipient: String,
+    pub amount: u128,
+}
+
+impl WalletTransferCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let recipient: Recipient = self.recipient.parse()?;
+        let event = client.transfer_to(&recipient, self.amount).await?;
+        println!("Transferred {} to {}", event.amount, event.to.to_string());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletClaimIndexCommand {
+    pub index: u32,
+}
+
+impl WalletClaimIndexCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client.claim_index(self.index).await?;
+        println!(
+            "Claimed index {} for {}",
+            event.index,
+            event.account.to_string()
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletFreeIndexCommand {
+    pub index: u32,
+}
+
+impl WalletFreeIndexCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client.free_index(self.index).await?;
+        println!("Freed index {}", event.index);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletGetIndexCommand {
+    pub index: u32,
+}
+
+impl WalletGetIndexCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        if let Some(account) = client.index_account(self.index).await? {
+            println!("{}", account.to_string());
+        } else {
+            println!("Index {} is unused", self.index);
+        }
+        Ok(())
+    }
+}
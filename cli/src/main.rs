@@ -4,10 +4,15 @@ use clap::Clap;
 use std::time::Duration;
 use sunshine_cli_utils::{set_key, Client as _, ConfigDirNotFound, Result};
 use sunshine_client::{identity::IdentityClient, Client};
-use sunshine_faucet_cli::MintCommand;
 use sunshine_identity_cli::key::KeySetCommand;
 
 mod command;
+mod escrow;
+mod faucet;
+mod fee;
+mod indices;
+mod proxy;
+mod vesting;
 
 #[async_std::main]
 async fn main() -> Result<()> {
@@ -45,9 +50,15 @@ async fn main() -> Result<()> {
             }) => {
                 let account_id = set_key(&mut client, paperkey, suri.as_deref(), force).await?;
                 println!("your device key is {}", account_id.to_string());
-                MintCommand.exec(&client).await?;
-                let uid = client.fetch_uid(&account_id).await?.unwrap();
-                println!("your user id is {}", uid);
+                // The key is set even if the faucet can't mint right now.
+                if let Err(err) = faucet::MintCommand.exec(&client).await {
+                    eprintln!("minting failed, retry with `account mint`: {}", err);
+                }
+                // Accounts get a uid once they are funded.
+                match client.fetch_uid(&account_id).await? {
+                    Some(uid) => println!("your user id is {}", uid),
+                    None => println!("your user id is assigned once your account is funded"),
+                }
                 Ok(())
             }
             KeySubCommand::Unlock(cmd) => cmd.exec(&mut client).await,
@@ -57,6 +68,7 @@ async fn main() -> Result<()> {
             AccountSubCommand::Create(cmd) => cmd.exec(&client).await,
             AccountSubCommand::Password(cmd) => cmd.exec(&client).await,
             AccountSubCommand::Mint(cmd) => cmd.exec(&client).await,
+            AccountSubCommand::MintStatus(cmd) => cmd.exec(&client).await,
         },
         SubCommand::Device(DeviceCommand { cmd }) => match cmd {
             DeviceSubCommand::Add(cmd) => cmd.exec(&client).await,
@@ -71,7 +83,12 @@ async fn main() -> Result<()> {
         },
         SubCommand::Wallet(WalletCommand { cmd }) => match cmd {
             WalletSubCommand::Balance(cmd) => cmd.exec(&client).await,
+            WalletSubCommand::Vest(cmd) => cmd.exec(&client).await,
             WalletSubCommand::Transfer(cmd) => cmd.exec(&client).await,
+            WalletSubCommand::EstimateFee(cmd) => cmd.exec(&client).await,
+            WalletSubCommand::ClaimIndex(cmd) => cmd.exec(&client).await,
+            WalletSubCommand::FreeIndex(cmd) => cmd.exec(&client).await,
+            WalletSubCommand::GetIndex(cmd) => cmd.exec(&client).await,
         },
         SubCommand::Bounty(BountyCommand { cmd }) => match cmd {
             BountySubCommand::PostBounty(cmd) => cmd.exec(&client).await,
@@ -82,6 +99,32 @@ async fn main() -> Result<()> {
             BountySubCommand::GetSubmission(cmd) => cmd.exec(&client).await,
             BountySubCommand::GetOpenBounties(cmd) => cmd.exec(&client).await,
             BountySubCommand::GetOpenSubmissions(cmd) => cmd.exec(&client).await,
+            BountySubCommand::Escrow(EscrowCommand { cmd }) => match cmd {
+                EscrowSubCommand::Post(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Contribute(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Submit(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Approve(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Reject(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::SetApprovers(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Cosign(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::SetVesting(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Get(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::GetSubmission(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::List(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Submissions(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Contributions(cmd) => cmd.exec(&client).await,
+                EscrowSubCommand::Pending(cmd) => cmd.exec(&client).await,
+            },
+            BountySubCommand::Dispute(DisputeCommand { cmd }) => match cmd {
+                DisputeSubCommand::Open(cmd) => cmd.exec(&client).await,
+                DisputeSubCommand::Vote(cmd) => cmd.exec(&client).await,
+                DisputeSubCommand::Get(cmd) => cmd.exec(&client).await,
+            },
+        },
+        SubCommand::Proxy(ProxyCommand { cmd }) => match cmd {
+            ProxySubCommand::Add(cmd) => cmd.exec(&client).await,
+            ProxySubCommand::Remove(cmd) => cmd.exec(&client).await,
+            ProxySubCommand::List(cmd) => cmd.exec(&client).await,
         },
         SubCommand::Run => loop {
             if let Some(sub) = password_changes.as_mut() {
@@ -0,0 +1,74 @@
+use crate::fee::InvalidAccountId;
+use clap::Clap;
+use sunshine_cli_utils::{Client as _, Result};
+use sunshine_client::proxy::{parse_proxy_type, proxy_type_name, ProxyClient};
+use sunshine_client::{AccountId, Client};
+
+#[derive(Clone, Debug, Clap)]
+pub struct ProxyAddCommand {
+    /// Account allowed to make calls on behalf of the device key.
+    pub delegate: String,
+    /// Calls the proxy may make.
+    #[clap(possible_values = &["any", "bounty-approver"], default_value = "bounty-approver")]
+    pub proxy_type: String,
+}
+
+impl ProxyAddCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let delegate: AccountId = self.delegate.parse().map_err(|_| InvalidAccountId)?;
+        let proxy_type = parse_proxy_type(&self.proxy_type)?;
+        client.add_proxy(&delegate, proxy_type).await?;
+        println!(
+            "Added {} as a {} proxy",
+            delegate.to_string(),
+            self.proxy_type
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ProxyRemoveCommand {
+    pub delegate: String,
+    #[clap(possible_values = &["any", "bounty-approver"], default_value = "bounty-approver")]
+    pub proxy_type: String,
+}
+
+impl ProxyRemoveCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let delegate: AccountId = self.delegate.parse().map_err(|_| InvalidAccountId)?;
+        let proxy_type = parse_proxy_type(&self.proxy_type)?;
+        client.remove_proxy(&delegate, proxy_type).await?;
+        println!(
+            "Removed {} as a {} proxy",
+            delegate.to_string(),
+            self.proxy_type
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct ProxyListCommand {
+    /// Account to list the proxies of, defaults to the account of the device
+    /// key.
+    pub account: Option<String>,
+}
+
+impl ProxyListCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let account: AccountId = if let Some(account) = &self.account {
+            account.parse().map_err(|_| InvalidAccountId)?
+        } else {
+            client.chain_signer()?.account_id().clone()
+        };
+        for proxy in client.proxies(&account).await? {
+            println!(
+                "{}: {}",
+                proxy.delegate.to_string(),
+                proxy_type_name(proxy.proxy_type)
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::fee::InvalidAccountId;
+use clap::Clap;
+use sunshine_cli_utils::{Client as _, Result};
+use sunshine_client::escrow::EscrowClient;
+use sunshine_client::{AccountId, Client};
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletBalanceCommand {
+    /// Account to show the balance of, defaults to the account of the device
+    /// key.
+    pub account: Option<String>,
+}
+
+impl WalletBalanceCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let account: AccountId = if let Some(account) = &self.account {
+            account.parse().map_err(|_| InvalidAccountId)?
+        } else {
+            client.chain_signer()?.account_id().clone()
+        };
+        let balance = client.vesting_balance(&account).await?;
+        let locked = balance.locked + balance.vested;
+        println!("free: {}", balance.free.saturating_sub(locked));
+        println!("locked: {}", balance.locked);
+        if balance.vested > 0 {
+            println!(
+                "vested: {} (unlocked with `sunshine-cli wallet vest`)",
+                balance.vested
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Clap)]
+pub struct WalletVestCommand {}
+
+impl WalletVestCommand {
+    pub async fn exec(&self, client: &Client) -> Result<()> {
+        let event = client.vest().await?;
+        println!(
+            "Unlocked the vested payouts of {}, {} is still locked",
+            event.account.to_string(),
+            event.locked
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! Offchain bodies of bounties and submissions.
+//!
+//! Bodies are stored as dag-cbor maps tagged with the version of the
+//! encoding and the kind of body. Bodies stored before they were versioned
+//! are plain github issues and decode as [`BountyBody::GithubIssue`]. Bodies
+//! of a newer version fail to decode with [`UnsupportedVersion`].
+use crate::GithubIssue;
+use libipld::cbor::DagCborCodec;
+use libipld::cid::Cid;
+use libipld::codec::{Codec, Decode, Encode};
+use libipld::error::Result;
+use libipld::{DagCbor, Ipld};
+use std::fmt;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+/// Version of the body encoding.
+const VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+#[error("Unsupported body version {0}, the client needs to be updated.")]
+pub struct UnsupportedVersion(pub i128);
+
+/// A GitLab merge request.
+#[derive(Clone, Debug, Eq, PartialEq, DagCbor)]
+pub struct GitlabMergeRequest {
+    /// Host of the GitLab instance, like `gitlab.com`.
+    pub host: String,
+    /// Path of the project including its namespace.
+    pub project: String,
+    pub iid: u64,
+}
+
+/// An issue of a Gitea instance.
+#[derive(Clone, Debug, Eq, PartialEq, DagCbor)]
+pub struct GiteaIssue {
+    pub host: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub issue_number: u64,
+}
+
+/// A file attached to a markdown spec, stored offchain.
+#[derive(Clone, Debug, Eq, PartialEq, DagCbor)]
+pub struct Attachment {
+    pub name: String,
+    pub cid: Cid,
+}
+
+/// A free-form spec written in markdown.
+#[derive(Clone, Debug, Eq, PartialEq, DagCbor)]
+pub struct MarkdownSpec {
+    pub title: String,
+    pub text: String,
+    pub attachments: Vec<Attachment>,
+}
+
+/// Body of a bounty or submission.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BountyBody {
+    GithubIssue(GithubIssue),
+    GitlabMergeRequest(GitlabMergeRequest),
+    GiteaIssue(GiteaIssue),
+    MarkdownSpec(MarkdownSpec),
+}
+
+impl BountyBody {
+    /// Returns the github issue of the body, if it is one.
+    pub fn github_issue(&self) -> Option<&GithubIssue> {
+        match self {
+            Self::GithubIssue(issue) => Some(issue),
+            _ => None,
+        }
+    }
+
+    /// Returns the repository of the body including its host, like
+    /// `github.com/<owner>/<repo>`. Markdown specs have no repository.
+    pub fn repo(&self) -> Option<String> {
+        match self {
+            Self::GithubIssue(issue) => Some(format!(
+                "github.com/{}/{}",
+                issue.repo_owner, issue.repo_name
+            )),
+            Self::GitlabMergeRequest(mr) => Some(format!("{}/{}", mr.host, mr.project)),
+            Self::GiteaIssue(issue) => Some(format!(
+                "{}/{}/{}",
+                issue.host, issue.repo_owner, issue.repo_name
+            )),
+            Self::MarkdownSpec(_) => None,
+        }
+    }
+}
+
+impl From<GithubIssue> for BountyBody {
+    fn from(issue: GithubIssue) -> Self {
+        Self::GithubIssue(issue)
+    }
+}
+
+impl fmt::Display for BountyBody {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::GithubIssue(issue) => write!(
+                f,
+                "https://github.com/{}/{}/issues/{}",
+                issue.repo_owner, issue.repo_name, issue.issue_number
+            ),
+            Self::GitlabMergeRequest(mr) => write!(
+                f,
+                "https://{}/{}/-/merge_requests/{}",
+                mr.host, mr.project, mr.iid
+            ),
+            Self::GiteaIssue(issue) => write!(
+                f,
+                "https://{}/{}/{}/issues/{}",
+                issue.host, issue.repo_owner, issue.repo_name, issue.issue_number
+            ),
+            Self::MarkdownSpec(spec) => write!(f, "{}", spec.title),
+        }
+    }
+}
+
+/// The tagged encoding of a `BountyBody`.
+#[derive(DagCbor)]
+enum Tagged {
+    GithubIssue(GithubIssue),
+    GitlabMergeRequest(GitlabMergeRequest),
+    GiteaIssue(GiteaIssue),
+    MarkdownSpec(MarkdownSpec),
+}
+
+#[derive(DagCbor)]
+struct Versioned {
+    version: u32,
+    body: Tagged,
+}
+
+impl Encode<DagCborCodec> for BountyBody {
+    fn encode<W: Write>(&self, c: DagCborCodec, w: &mut W) -> Result<()> {
+        let body = match self.clone() {
+            Self::GithubIssue(issue) => Tagged::GithubIssue(issue),
+            Self::GitlabMergeRequest(mr) => Tagged::GitlabMergeRequest(mr),
+            Self::GiteaIssue(issue) => Tagged::GiteaIssue(issue),
+            Self::MarkdownSpec(spec) => Tagged::MarkdownSpec(spec),
+        };
+        Versioned {
+            version: VERSION,
+            body,
+        }
+        .encode(c, w)
+    }
+}
+
+impl Decode<DagCborCodec> for BountyBody {
+    /// Bodies are stored as blocks of their own, so the rest of the reader
+    /// is the body.
+    fn decode<R: Read>(c: DagCborCodec, r: &mut R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+        // The version is read first, a newer version may have kinds of
+        // bodies this client doesn't know.
+        let version = match c.decode::<Ipld>(&bytes)? {
+            Ipld::Map(map) => match map.get("version") {
+                Some(Ipld::Integer(version)) => Some(*version),
+                _ => None,
+            },
+            _ => None,
+        };
+        match version {
+            None => Ok(Self::GithubIssue(c.decode::<GithubIssue>(&bytes)?)),
+            Some(version) if version == VERSION as i128 => {
+                Ok(match c.decode::<Versioned>(&bytes)?.body {
+                    Tagged::GithubIssue(issue) => Self::GithubIssue(issue),
+                    Tagged::GitlabMergeRequest(mr) => Self::GitlabMergeRequest(mr),
+                    Tagged::GiteaIssue(issue) => Self::GiteaIssue(issue),
+                    Tagged::MarkdownSpec(spec) => Self::MarkdownSpec(spec),
+                })
+            }
+            Some(version) => Err(UnsupportedVersion(version).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libipld::block::Block;
+    use sunshine_client_utils::codec::hasher::BLAKE2B_256;
+
+    fn github_issue() -> GithubIssue {
+        GithubIssue {
+            issue_number: 42,
+            repo_owner: "sunshine-protocol".into(),
+            repo_name: "sunshine".into(),
+        }
+    }
+
+    fn round_trip(body: BountyBody) {
+        let block = Block::encode(DagCborCodec, BLAKE2B_256, &body).unwrap();
+        assert_eq!(block.decode::<DagCborCodec, BountyBody>().unwrap(), body);
+    }
+
+    #[test]
+    fn legacy_github_issue_decodes() {
+        let block = Block::encode(DagCborCodec, BLAKE2B_256, &github_issue()).unwrap();
+        assert_eq!(
+            block.decode::<DagCborCodec, BountyBody>().unwrap(),
+            BountyBody::GithubIssue(github_issue())
+        );
+    }
+
+    #[test]
+    fn bodies_round_trip() {
+        round_trip(BountyBody::GithubIssue(github_issue()));
+        round_trip(BountyBody::GitlabMergeRequest(GitlabMergeRequest {
+            host: "gitlab.com".into(),
+            project: "group/subgroup/project".into(),
+            iid: 7,
+        }));
+        round_trip(BountyBody::GiteaIssue(GiteaIssue {
+            host: "gitea.com".into(),
+            repo_owner: "owner".into(),
+            repo_name: "repo".into(),
+            issue_number: 3,
+        }));
+        round_trip(BountyBody::MarkdownSpec(MarkdownSpec {
+            title: "Spec".into(),
+            text: "# Spec\n\nDetails.".into(),
+            attachments: vec![Attachment {
+                name: "design.pdf".into(),
+                cid: *Block::encode(DagCborCodec, BLAKE2B_256, &github_issue())
+                    .unwrap()
+                    .cid(),
+            }],
+        }));
+    }
+
+    #[test]
+    fn newer_version_is_unsupported() {
+        let newer = Versioned {
+            version: VERSION + 1,
+            body: Tagged::GithubIssue(github_issue()),
+        };
+        let block = Block::encode(DagCborCodec, BLAKE2B_256, &newer).unwrap();
+        let err = block.decode::<DagCborCodec, BountyBody>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            UnsupportedVersion(VERSION as i128 + 1).to_string()
+        );
+    }
+}
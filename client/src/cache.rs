@@ -0,0 +1,380 @@
+//! Caches of the offchain client.
+//!
+//! Values are looked up in an in-memory LRU cache, then in an optional
+//! on-disk LRU cache of encoded blocks and finally in the store. Values read
+//! from the store are added to both tiers.
+use async_trait::async_trait;
+use libipld::block::Block;
+use libipld::cache::Cache;
+use libipld::cbor::DagCborCodec;
+use libipld::cid::Cid;
+use libipld::codec::{Codec, Decode, Encode};
+use libipld::error::Result;
+use libipld::store::Store;
+use lru::LruCache;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use sunshine_client_utils::codec::hasher::BLAKE2B_256;
+
+/// Configuration of the offchain caches.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CacheConfig {
+    /// Number of values kept in memory per cached type.
+    pub size: usize,
+    /// Directory of the on-disk cache, disabled if `None`.
+    pub disk_path: Option<PathBuf>,
+    /// Number of blocks kept on disk per cached type.
+    pub disk_size: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            size: 64,
+            disk_path: None,
+            disk_size: 4096,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Reads the configuration from `SUNSHINE_CACHE_SIZE`, `SUNSHINE_CACHE_DIR`
+    /// and `SUNSHINE_CACHE_DISK_SIZE`, using the defaults for unset variables.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(size) = env_var("SUNSHINE_CACHE_SIZE").and_then(|size| size.parse().ok()) {
+            config.size = size;
+        }
+        if let Some(path) = env_var("SUNSHINE_CACHE_DIR") {
+            config.disk_path = Some(path.into());
+        }
+        if let Some(size) = env_var("SUNSHINE_CACHE_DISK_SIZE").and_then(|size| size.parse().ok()) {
+            config.disk_size = size;
+        }
+        config
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Lookups of the offchain caches.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    /// Values found in memory.
+    pub hits: u64,
+    /// Values found on disk.
+    pub disk_hits: u64,
+    /// Values fetched from the store.
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct CacheMetrics {
+    hits: AtomicU64,
+    disk_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheMetrics {
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// LRU cache of encoded blocks, stored as one file per cid.
+///
+/// The recency of the blocks is restored from the modification times of the
+/// files when the cache is opened.
+struct DiskCache {
+    path: PathBuf,
+    blocks: Mutex<LruCache<Cid, ()>>,
+}
+
+impl DiskCache {
+    fn open(path: &Path, size: usize) -> io::Result<Self> {
+        fs::create_dir_all(path)?;
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let cid = match entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse().ok())
+            {
+                Some(cid) => cid,
+                None => continue,
+            };
+            let modified = entry
+                .metadata()?
+                .modified()
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((modified, cid));
+        }
+        files.sort();
+        let cache = Self {
+            path: path.to_path_buf(),
+            blocks: Mutex::new(LruCache::new(size.max(1))),
+        };
+        for (_, cid) in files {
+            cache.touch(cid);
+        }
+        Ok(cache)
+    }
+
+    fn file(&self, cid: &Cid) -> PathBuf {
+        self.path.join(cid.to_string())
+    }
+
+    /// Marks `cid` as most recently used, removing the file of the least
+    /// recently used block if the cache is full.
+    fn touch(&self, cid: Cid) {
+        let mut blocks = self.blocks.lock().unwrap();
+        if !blocks.contains(&cid) && blocks.len() == blocks.cap() {
+            if let Some((evicted, ())) = blocks.pop_lru() {
+                fs::remove_file(self.file(&evicted)).ok();
+            }
+        }
+        blocks.put(cid, ());
+    }
+
+    fn get(&self, cid: &Cid) -> Option<Vec<u8>> {
+        if !self.blocks.lock().unwrap().contains(cid) {
+            return None;
+        }
+        match fs::read(self.file(cid)) {
+            Ok(data) => {
+                self.touch(cid.clone());
+                Some(data)
+            }
+            Err(_) => {
+                self.blocks.lock().unwrap().pop(cid);
+                None
+            }
+        }
+    }
+
+    fn insert(&self, cid: &Cid, data: &[u8]) {
+        match fs::write(self.file(cid), data) {
+            Ok(()) => self.touch(cid.clone()),
+            Err(err) => log::warn!("failed to write {} to the disk cache: {}", cid, err),
+        }
+    }
+}
+
+/// Cache of the values of type `T` in `store`, encoded with dag-cbor.
+pub struct TieredCache<S, T> {
+    store: S,
+    memory: Mutex<LruCache<Cid, T>>,
+    disk: Option<DiskCache>,
+    metrics: Arc<CacheMetrics>,
+}
+
+impl<S, T> TieredCache<S, T> {
+    /// Creates the cache. Values of different types are kept in different
+    /// subdirectories `name` of the on-disk cache.
+    pub(crate) fn new(
+        store: S,
+        config: &CacheConfig,
+        name: &str,
+        metrics: Arc<CacheMetrics>,
+    ) -> Self {
+        let disk = config.disk_path.as_ref().and_then(|path| {
+            DiskCache::open(&path.join(name), config.disk_size)
+                .map_err(|err| log::warn!("disk cache disabled: {}", err))
+                .ok()
+        });
+        Self {
+            store,
+            memory: Mutex::new(LruCache::new(config.size.max(1))),
+            disk,
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, T> Cache<S, DagCborCodec, T> for TieredCache<S, T>
+where
+    S: Store,
+    T: Decode<DagCborCodec> + Encode<DagCborCodec> + Clone + Send + Sync,
+{
+    async fn get(&self, cid: &Cid) -> Result<T> {
+        if let Some(value) = self.memory.lock().unwrap().get(cid).cloned() {
+            self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+        if let Some(data) = self.disk.as_ref().and_then(|disk| disk.get(cid)) {
+            if let Ok(value) = DagCborCodec.decode::<T>(&data) {
+                self.metrics.disk_hits.fetch_add(1, Ordering::Relaxed);
+                self.memory.lock().unwrap().put(cid.clone(), value.clone());
+                return Ok(value);
+            }
+        }
+        self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+        let block = self.store.get(cid).await?;
+        let value: T = block.decode::<DagCborCodec, _>()?;
+        if let Some(disk) = &self.disk {
+            disk.insert(cid, &block.data);
+        }
+        self.memory.lock().unwrap().put(cid.clone(), value.clone());
+        Ok(value)
+    }
+
+    async fn insert(&self, value: T) -> Result<Cid> {
+        let block = Block::encode(DagCborCodec, BLAKE2B_256, &value)?;
+        self.store.insert(&block).await?;
+        if let Some(disk) = &self.disk {
+            disk.insert(&block.cid, &block.data);
+        }
+        self.memory.lock().unwrap().put(block.cid.clone(), value);
+        Ok(block.cid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BountyBody;
+    use crate::{GithubIssue, OffchainClient};
+    use libipld::mem::MemStore;
+    use std::collections::HashSet;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn body(issue_number: u64) -> BountyBody {
+        BountyBody::GithubIssue(GithubIssue {
+            issue_number,
+            repo_owner: "sunshine-protocol".into(),
+            repo_name: "sunshine".into(),
+        })
+    }
+
+    fn block(issue_number: u64) -> Block {
+        Block::encode(DagCborCodec, BLAKE2B_256, &body(issue_number)).unwrap()
+    }
+
+    fn config(dir: &TempDir) -> CacheConfig {
+        CacheConfig {
+            size: 1,
+            disk_path: Some(dir.path().to_path_buf()),
+            disk_size: 2,
+        }
+    }
+
+    #[test]
+    fn disk_cache_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let disk = DiskCache::open(dir.path(), 2).unwrap();
+        let (a, b, c) = (block(1), block(2), block(3));
+        disk.insert(&a.cid, &a.data);
+        disk.insert(&b.cid, &b.data);
+        assert_eq!(disk.get(&a.cid).as_deref(), Some(&a.data[..]));
+
+        disk.insert(&c.cid, &c.data);
+        assert!(disk.get(&b.cid).is_none());
+        assert!(!disk.file(&b.cid).exists());
+        assert!(disk.get(&a.cid).is_some());
+        assert!(disk.get(&c.cid).is_some());
+    }
+
+    #[test]
+    fn disk_cache_restores_recency() {
+        let dir = TempDir::new().unwrap();
+        let (a, b, c) = (block(1), block(2), block(3));
+        let disk = DiskCache::open(dir.path(), 2).unwrap();
+        for block in &[&a, &b] {
+            disk.insert(&block.cid, &block.data);
+            // Keeps the modification times of the files apart.
+            thread::sleep(Duration::from_millis(10));
+        }
+        drop(disk);
+
+        let disk = DiskCache::open(dir.path(), 2).unwrap();
+        disk.insert(&c.cid, &c.data);
+        assert!(disk.get(&a.cid).is_none());
+        assert!(disk.get(&b.cid).is_some());
+        assert!(disk.get(&c.cid).is_some());
+    }
+
+    #[async_std::test]
+    async fn tiered_cache_counts_lookups() {
+        let dir = TempDir::new().unwrap();
+        let store = MemStore::default();
+        let metrics = Arc::new(CacheMetrics::default());
+        let cache = TieredCache::new(store.clone(), &config(&dir), "bounties", metrics.clone());
+        let cid = cache.insert(body(1)).await.unwrap();
+        assert_eq!(cache.get(&cid).await.unwrap(), body(1));
+        assert_eq!(
+            metrics.stats(),
+            CacheStats {
+                hits: 1,
+                disk_hits: 0,
+                misses: 0
+            }
+        );
+
+        // A new cache finds the value on disk.
+        let cache = TieredCache::new(store.clone(), &config(&dir), "bounties", metrics.clone());
+        assert_eq!(cache.get(&cid).await.unwrap(), body(1));
+        assert_eq!(
+            metrics.stats(),
+            CacheStats {
+                hits: 1,
+                disk_hits: 1,
+                misses: 0
+            }
+        );
+
+        // A cache without a disk tier fetches it from the store.
+        let cache = TieredCache::new(store, &CacheConfig::default(), "bounties", metrics.clone());
+        assert_eq!(cache.get(&cid).await.unwrap(), body(1));
+        assert_eq!(cache.get(&cid).await.unwrap(), body(1));
+        assert_eq!(
+            metrics.stats(),
+            CacheStats {
+                hits: 2,
+                disk_hits: 1,
+                misses: 1
+            }
+        );
+    }
+
+    #[async_std::test]
+    async fn set_pins_unpins_previous_pins() {
+        let store = MemStore::default();
+        let client = OffchainClient::new(store.clone());
+        let (a, b) = (block(1), block(2));
+        store.insert(&a).await.unwrap();
+        store.insert(&b).await.unwrap();
+
+        let pins = vec![a.cid.clone()].into_iter().collect::<HashSet<_>>();
+        client.set_pins(pins).await.unwrap();
+        assert_eq!(
+            store.resolve(&crate::pin_alias(&a.cid)).await.unwrap(),
+            Some(a.cid.clone())
+        );
+
+        let pins = vec![b.cid.clone()].into_iter().collect::<HashSet<_>>();
+        client.set_pins(pins.clone()).await.unwrap();
+        assert_eq!(
+            store.resolve(&crate::pin_alias(&a.cid)).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            store.resolve(&crate::pin_alias(&b.cid)).await.unwrap(),
+            Some(b.cid.clone())
+        );
+        assert_eq!(*client.pins.lock().unwrap(), pins);
+    }
+}
@@ -0,0 +1,762 @@
+//! Client for bounties that pay out in milestones.
+use crate::body::BountyBody;
+use crate::bounty::BountyClient;
+use crate::proxy::ProxyClient;
+use crate::runtime_api::state_call;
+use crate::utility::{find_events, BatchAllCall, BatchAllCallExt};
+use crate::{AccountId, Client, Runtime, Uid};
+use async_trait::async_trait;
+use core::marker::PhantomData;
+use frame_support::Parameter;
+use libipld::cache::Cache;
+use parity_scale_codec::{Decode, Encode};
+use std::collections::HashSet;
+use substrate_subxt::balances::{Balances, BalancesEventsDecoder};
+use substrate_subxt::sp_runtime::traits::{AtLeast32Bit, Member};
+use substrate_subxt::system::{System, SystemEventsDecoder};
+use substrate_subxt::{module, Call, Event, Store};
+use sunshine_client_utils::codec::Cid;
+use sunshine_client_utils::{Client as _, Result};
+use thiserror::Error;
+
+pub type BalanceOf<T> = <T as Balances>::Balance;
+
+#[module]
+pub trait Escrow: System + Balances {
+    type IpfsReference: Parameter + Member + Default;
+    type EscrowId: Parameter + Member + AtLeast32Bit + Default + Copy;
+    type SubmissionId: Parameter + Member + AtLeast32Bit + Default + Copy;
+    type Uid: Parameter + Member + AtLeast32Bit + Default + Copy;
+}
+
+// The types the pallet stores and returns from its runtime api are shared
+// with the pallet, so their encoding can't drift apart.
+pub use sunshine_escrow_pallet::{
+    ApproverSet, Dispute, EscrowBounty, MilestoneSubmission, VestingBalance,
+};
+
+pub type EscrowBountyOf<T> = EscrowBounty<
+    <T as System>::AccountId,
+    <T as Escrow>::IpfsReference,
+    BalanceOf<T>,
+    <T as System>::BlockNumber,
+>;
+pub type MilestoneSubmissionOf<T> =
+    MilestoneSubmission<<T as System>::AccountId, <T as Escrow>::IpfsReference>;
+pub type ApproverSetOf<T> = ApproverSet<<T as Escrow>::Uid>;
+pub type DisputeOf<T> =
+    Dispute<<T as System>::AccountId, <T as Escrow>::IpfsReference, BalanceOf<T>>;
+
+// ------------------------------------------------------------------------------
+// Storage
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct BountiesStore<T: Escrow> {
+    #[store(returns = Option<EscrowBountyOf<T>>)]
+    pub id: T::EscrowId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct ContributionsStore<'a, T: Escrow> {
+    #[store(returns = BalanceOf<T>)]
+    pub id: T::EscrowId,
+    pub account: &'a <T as System>::AccountId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct SubmissionsStore<T: Escrow> {
+    #[store(returns = Option<MilestoneSubmissionOf<T>>)]
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct DisputesStore<T: Escrow> {
+    #[store(returns = Option<DisputeOf<T>>)]
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct ApproversStore<T: Escrow> {
+    #[store(returns = Option<ApproverSetOf<T>>)]
+    pub id: T::EscrowId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct ApprovalsStore<T: Escrow> {
+    #[store(returns = Vec<T::Uid>)]
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct VestingPeriodsStore<T: Escrow> {
+    #[store(returns = Option<<T as System>::BlockNumber>)]
+    pub id: T::EscrowId,
+}
+
+// ------------------------------------------------------------------------------
+// Calls
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct PostBountyCall<T: Escrow> {
+    pub description: T::IpfsReference,
+    pub milestones: Vec<BalanceOf<T>>,
+    pub deposit: BalanceOf<T>,
+    pub lifetime: <T as System>::BlockNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct ContributeCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub amount: BalanceOf<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct SubmitCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub milestone: u32,
+    pub submission: T::IpfsReference,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct ApproveCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct RejectCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct OpenDisputeCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub evidence: T::IpfsReference,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct VoteDisputeCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub approve: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct SetApproversCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub uids: Vec<T::Uid>,
+    pub threshold: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct SetVestingCall<T: Escrow> {
+    pub id: T::EscrowId,
+    pub period: <T as System>::BlockNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct VestCall<T: Escrow> {
+    pub _runtime: PhantomData<T>,
+}
+
+// ------------------------------------------------------------------------------
+// Events
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct EscrowPostedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub poster: <T as System>::AccountId,
+    pub deposit: BalanceOf<T>,
+    pub description: T::IpfsReference,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct EscrowContributionEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub contributor: <T as System>::AccountId,
+    pub amount: BalanceOf<T>,
+    pub total: BalanceOf<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct MilestoneSubmittedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub milestone: u32,
+    pub submitter: <T as System>::AccountId,
+    pub submission: T::IpfsReference,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct MilestonePaidEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub milestone: u32,
+    pub submitter: <T as System>::AccountId,
+    pub amount: BalanceOf<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct EscrowCompletedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct EscrowRefundedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub contributor: <T as System>::AccountId,
+    pub amount: BalanceOf<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct EscrowExpiredEvent<T: Escrow> {
+    pub id: T::EscrowId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct MilestoneRejectedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct DisputeOpenedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub submitter: <T as System>::AccountId,
+    pub bond: BalanceOf<T>,
+    pub evidence: T::IpfsReference,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct DisputeVotedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub arbiter: <T as System>::AccountId,
+    pub approve: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct DisputeResolvedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub approved: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct ApproversSetEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub threshold: u32,
+    pub uids: Vec<T::Uid>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct MilestoneApprovedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+    pub uid: T::Uid,
+    pub approvals: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct VestingSetEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub period: <T as System>::BlockNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct VestingStartedEvent<T: Escrow> {
+    pub account: <T as System>::AccountId,
+    pub amount: BalanceOf<T>,
+    pub period: <T as System>::BlockNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct VestedEvent<T: Escrow> {
+    pub account: <T as System>::AccountId,
+    pub locked: BalanceOf<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct DisputeDismissedEvent<T: Escrow> {
+    pub id: T::EscrowId,
+    pub submission_id: T::SubmissionId,
+}
+
+// ------------------------------------------------------------------------------
+// Client
+// ------------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+#[error("Event not found.")]
+pub struct EventNotFound;
+
+#[async_trait]
+pub trait EscrowClient {
+    /// Stores the description offchain and posts a bounty paying `milestones`
+    /// that expires `lifetime` blocks from now.
+    async fn post_escrow(
+        &self,
+        description: BountyBody,
+        milestones: Vec<BalanceOf<Runtime>>,
+        deposit: BalanceOf<Runtime>,
+        lifetime: <Runtime as System>::BlockNumber,
+    ) -> Result<EscrowPostedEvent<Runtime>>;
+
+    /// Contributes `amount` to bounty `id`.
+    async fn contribute_to_escrow(
+        &self,
+        id: u64,
+        amount: BalanceOf<Runtime>,
+    ) -> Result<EscrowContributionEvent<Runtime>>;
+
+    /// Stores the submission offchain and submits it for a milestone.
+    async fn submit_milestone(
+        &self,
+        id: u64,
+        milestone: u32,
+        submission: BountyBody,
+    ) -> Result<MilestoneSubmittedEvent<Runtime>>;
+
+    /// Approves a submission and pays out its milestone.
+    async fn approve_milestone(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<MilestonePaidEvent<Runtime>>;
+
+    /// Approves the submissions of bounty `id` in one batch, which fails as a
+    /// whole if one approval fails. With `real` the signer approves on behalf
+    /// of the poster, who has to have added the signer as a proxy.
+    async fn approve_milestones(
+        &self,
+        id: u64,
+        submission_ids: &[u64],
+        real: Option<&AccountId>,
+    ) -> Result<Vec<MilestonePaidEvent<Runtime>>>;
+
+    /// Hands the approval of submissions of bounty `id` over to `uids`, of
+    /// which `threshold` have to approve a submission to pay it out.
+    async fn set_escrow_approvers(
+        &self,
+        id: u64,
+        uids: Vec<Uid>,
+        threshold: u32,
+    ) -> Result<ApproversSetEvent<Runtime>>;
+
+    /// Approves a submission of a team bounty on behalf of the uid of the
+    /// signer. Returns the payout if the approval reached the threshold.
+    async fn cosign_milestone(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<(
+        MilestoneApprovedEvent<Runtime>,
+        Option<MilestonePaidEvent<Runtime>>,
+    )>;
+
+    /// Makes the payouts of bounty `id` vest linearly over `period` blocks.
+    async fn set_escrow_vesting(
+        &self,
+        id: u64,
+        period: <Runtime as System>::BlockNumber,
+    ) -> Result<VestingSetEvent<Runtime>>;
+
+    /// Unlocks the vested payouts of the signer.
+    async fn vest(&self) -> Result<VestedEvent<Runtime>>;
+
+    /// Rejects a submission, which allows the submitter to dispute it.
+    async fn reject_milestone(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<MilestoneRejectedEvent<Runtime>>;
+
+    /// Disputes a submission, reserving the dispute bond.
+    async fn open_dispute(
+        &self,
+        id: u64,
+        submission_id: u64,
+        evidence: Cid,
+    ) -> Result<DisputeOpenedEvent<Runtime>>;
+
+    /// Votes on a dispute as an arbiter.
+    async fn vote_dispute(
+        &self,
+        id: u64,
+        submission_id: u64,
+        approve: bool,
+    ) -> Result<DisputeVotedEvent<Runtime>>;
+
+    /// Returns the bounty with `id`.
+    async fn escrow(&self, id: u64) -> Result<Option<EscrowBountyOf<Runtime>>>;
+
+    /// Returns the submission with `submission_id` of bounty `id`.
+    async fn milestone_submission(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<Option<MilestoneSubmissionOf<Runtime>>>;
+
+    /// Returns the dispute about submission `submission_id` of bounty `id`.
+    async fn dispute(&self, id: u64, submission_id: u64) -> Result<Option<DisputeOf<Runtime>>>;
+
+    /// Returns the approvers of bounty `id` if it is a team bounty.
+    async fn escrow_approvers(&self, id: u64) -> Result<Option<ApproverSetOf<Runtime>>>;
+
+    /// Returns the number of blocks the payouts of bounty `id` vest over.
+    async fn escrow_vesting_period(
+        &self,
+        id: u64,
+    ) -> Result<Option<<Runtime as System>::BlockNumber>>;
+
+    /// Returns the uids that approved submission `submission_id` of bounty
+    /// `id`.
+    async fn escrow_approvals(&self, id: u64, submission_id: u64) -> Result<Vec<Uid>>;
+
+    /// Returns `limit` open bounties starting at `offset` with at least
+    /// `min_total` contributed, ordered by the total from highest to lowest.
+    async fn open_escrows(
+        &self,
+        min_total: BalanceOf<Runtime>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<(u64, EscrowBountyOf<Runtime>)>>;
+
+    /// Returns the submissions for bounty `id`.
+    async fn escrow_submissions(
+        &self,
+        id: u64,
+    ) -> Result<Vec<(u64, MilestoneSubmissionOf<Runtime>)>>;
+
+    /// Returns the contributions of `account` to open bounties.
+    async fn escrow_contributions(
+        &self,
+        account: &<Runtime as System>::AccountId,
+    ) -> Result<Vec<(u64, BalanceOf<Runtime>)>>;
+
+    /// Returns the submissions of team bounties waiting for an approval of
+    /// `uid`, with the uids that already approved them.
+    async fn pending_approvals(&self, uid: Uid) -> Result<Vec<(u64, u64, Vec<Uid>)>>;
+
+    /// Returns the free balance of `account` and how much of it is locked by
+    /// vesting payouts.
+    async fn vesting_balance(
+        &self,
+        account: &<Runtime as System>::AccountId,
+    ) -> Result<VestingBalance<BalanceOf<Runtime>>>;
+
+    /// Pins the descriptions and submissions of the open bounties in the
+    /// offchain store, so they aren't garbage collected, and unpins the ones
+    /// of bounties that closed since the last call. The open bounties of the
+    /// legacy bounty pallet are pinned as well. Returns the number of pinned
+    /// cids.
+    async fn pin_open_escrows(&self) -> Result<usize>;
+}
+
+#[async_trait]
+impl EscrowClient for Client {
+    async fn post_escrow(
+        &self,
+        description: BountyBody,
+        milestones: Vec<BalanceOf<Runtime>>,
+        deposit: BalanceOf<Runtime>,
+        lifetime: <Runtime as System>::BlockNumber,
+    ) -> Result<EscrowPostedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        let description = self.offchain_client().insert(description).await?;
+        Ok(self
+            .chain_client()
+            .post_bounty_and_watch(signer, description, milestones, deposit, lifetime)
+            .await?
+            .escrow_posted()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn contribute_to_escrow(
+        &self,
+        id: u64,
+        amount: BalanceOf<Runtime>,
+    ) -> Result<EscrowContributionEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .contribute_and_watch(signer, id, amount)
+            .await?
+            .escrow_contribution()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn submit_milestone(
+        &self,
+        id: u64,
+        milestone: u32,
+        submission: BountyBody,
+    ) -> Result<MilestoneSubmittedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        let submission = self.offchain_client().insert(submission).await?;
+        Ok(self
+            .chain_client()
+            .submit_and_watch(signer, id, milestone, submission)
+            .await?
+            .milestone_submitted()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn approve_milestone(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<MilestonePaidEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .approve_and_watch(signer, id, submission_id)
+            .await?
+            .milestone_paid()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn approve_milestones(
+        &self,
+        id: u64,
+        submission_ids: &[u64],
+        real: Option<&AccountId>,
+    ) -> Result<Vec<MilestonePaidEvent<Runtime>>> {
+        let client = self.chain_client();
+        let calls = submission_ids
+            .iter()
+            .map(|submission_id| {
+                client.encode(ApproveCall::<Runtime> {
+                    id,
+                    submission_id: *submission_id,
+                })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let result = if let Some(real) = real {
+            let batch = client.encode(BatchAllCall::<Runtime> {
+                calls: &calls,
+                _runtime: PhantomData,
+            })?;
+            self.proxy_call(real, &batch).await?
+        } else {
+            let signer = self.chain_signer()?;
+            client.batch_all_and_watch(signer, &calls).await?
+        };
+        let events = find_events::<MilestonePaidEvent<Runtime>>(&result)?;
+        if events.is_empty() {
+            return Err(EventNotFound.into());
+        }
+        Ok(events)
+    }
+
+    async fn set_escrow_approvers(
+        &self,
+        id: u64,
+        uids: Vec<Uid>,
+        threshold: u32,
+    ) -> Result<ApproversSetEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .set_approvers_and_watch(signer, id, uids, threshold)
+            .await?
+            .approvers_set()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn cosign_milestone(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<(
+        MilestoneApprovedEvent<Runtime>,
+        Option<MilestonePaidEvent<Runtime>>,
+    )> {
+        let signer = self.chain_signer()?;
+        let result = self
+            .chain_client()
+            .approve_and_watch(signer, id, submission_id)
+            .await?;
+        let approved = result.milestone_approved()?.ok_or(EventNotFound)?;
+        Ok((approved, result.milestone_paid()?))
+    }
+
+    async fn set_escrow_vesting(
+        &self,
+        id: u64,
+        period: <Runtime as System>::BlockNumber,
+    ) -> Result<VestingSetEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .set_vesting_and_watch(signer, id, period)
+            .await?
+            .vesting_set()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn vest(&self) -> Result<VestedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .vest_and_watch(signer)
+            .await?
+            .vested()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn reject_milestone(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<MilestoneRejectedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .reject_and_watch(signer, id, submission_id)
+            .await?
+            .milestone_rejected()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn open_dispute(
+        &self,
+        id: u64,
+        submission_id: u64,
+        evidence: Cid,
+    ) -> Result<DisputeOpenedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .open_dispute_and_watch(signer, id, submission_id, evidence)
+            .await?
+            .dispute_opened()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn vote_dispute(
+        &self,
+        id: u64,
+        submission_id: u64,
+        approve: bool,
+    ) -> Result<DisputeVotedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .vote_dispute_and_watch(signer, id, submission_id, approve)
+            .await?
+            .dispute_voted()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn escrow(&self, id: u64) -> Result<Option<EscrowBountyOf<Runtime>>> {
+        Ok(self.chain_client().bounties(id, None).await?)
+    }
+
+    async fn milestone_submission(
+        &self,
+        id: u64,
+        submission_id: u64,
+    ) -> Result<Option<MilestoneSubmissionOf<Runtime>>> {
+        Ok(self
+            .chain_client()
+            .submissions(id, submission_id, None)
+            .await?)
+    }
+
+    async fn escrow_approvers(&self, id: u64) -> Result<Option<ApproverSetOf<Runtime>>> {
+        Ok(self.chain_client().approvers(id, None).await?)
+    }
+
+    async fn escrow_vesting_period(
+        &self,
+        id: u64,
+    ) -> Result<Option<<Runtime as System>::BlockNumber>> {
+        Ok(self.chain_client().vesting_periods(id, None).await?)
+    }
+
+    async fn escrow_approvals(&self, id: u64, submission_id: u64) -> Result<Vec<Uid>> {
+        Ok(self
+            .chain_client()
+            .approvals(id, submission_id, None)
+            .await?)
+    }
+
+    async fn dispute(&self, id: u64, submission_id: u64) -> Result<Option<DisputeOf<Runtime>>> {
+        Ok(self
+            .chain_client()
+            .disputes(id, submission_id, None)
+            .await?)
+    }
+
+    async fn open_escrows(
+        &self,
+        min_total: BalanceOf<Runtime>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<(u64, EscrowBountyOf<Runtime>)>> {
+        state_call(self, "BountyApi_open_bounties", (min_total, offset, limit)).await
+    }
+
+    async fn escrow_submissions(
+        &self,
+        id: u64,
+    ) -> Result<Vec<(u64, MilestoneSubmissionOf<Runtime>)>> {
+        state_call(self, "BountyApi_submissions", id).await
+    }
+
+    async fn escrow_contributions(
+        &self,
+        account: &<Runtime as System>::AccountId,
+    ) -> Result<Vec<(u64, BalanceOf<Runtime>)>> {
+        state_call(self, "BountyApi_contributions", account).await
+    }
+
+    async fn pending_approvals(&self, uid: Uid) -> Result<Vec<(u64, u64, Vec<Uid>)>> {
+        state_call(self, "BountyApi_pending_approvals", uid).await
+    }
+
+    async fn vesting_balance(
+        &self,
+        account: &<Runtime as System>::AccountId,
+    ) -> Result<VestingBalance<BalanceOf<Runtime>>> {
+        state_call(self, "BountyApi_vesting_balance", account).await
+    }
+
+    async fn pin_open_escrows(&self) -> Result<usize> {
+        const PAGE: u32 = 100;
+        let mut cids = HashSet::new();
+        let mut offset = 0;
+        loop {
+            let bounties = self.open_escrows(0, offset, PAGE).await?;
+            for (id, bounty) in &bounties {
+                cids.insert(bounty.description.clone());
+                for (_, submission) in self.escrow_submissions(*id).await? {
+                    cids.insert(submission.submission);
+                }
+            }
+            if bounties.len() < PAGE as usize {
+                break;
+            }
+            offset += PAGE;
+        }
+        for (id, bounty) in self.open_bounties(0).await?.unwrap_or_default() {
+            cids.insert(bounty.info());
+            for (_, submission) in self.open_submissions(id).await?.unwrap_or_default() {
+                cids.insert(submission.submission());
+            }
+        }
+        let pinned = cids.len();
+        self.offchain_client().set_pins(cids).await?;
+        Ok(pinned)
+    }
+}
@@ -0,0 +1,335 @@
+//! Typed stream of the events of the sunshine chain.
+//!
+//! The events of every new block are fetched from `System.Events` and
+//! decoded into a [`SunshineEvent`], together with the block and extrinsic
+//! that emitted them. Events without a variant, or whose arguments fail to
+//! decode, are passed on as [`SunshineEvent::Other`].
+use crate::bounty::{
+    BountyEventsDecoder, BountyPaymentExecutedEvent, BountyPostedEvent,
+    BountyRaiseContributionEvent, BountySubmissionPostedEvent,
+};
+use crate::escrow::{self, EscrowEventsDecoder};
+use crate::faucet::{FaucetEventsDecoder, MintedEvent};
+use crate::identity::{
+    AccountCreatedEvent, IdentityChangedEvent, IdentityEventsDecoder, PasswordChangedEvent,
+};
+use crate::indices::IndicesEventsDecoder;
+use crate::proxy::ProxyEventsDecoder;
+use crate::utility::UtilityEventsDecoder;
+use crate::{Client, Runtime};
+use async_trait::async_trait;
+use jsonrpsee::client::Subscription;
+use jsonrpsee::common::{to_value as to_json_value, Params};
+use parity_scale_codec::Decode;
+use std::collections::VecDeque;
+use substrate_subxt::balances::{BalancesEventsDecoder, EndowedEvent, TransferEvent};
+use substrate_subxt::sp_core::storage::{StorageData, StorageKey};
+use substrate_subxt::sp_core::twox_128;
+use substrate_subxt::sp_runtime::traits::Header as _;
+use substrate_subxt::sp_runtime::Perbill;
+use substrate_subxt::system::{Phase, System, SystemEventsDecoder};
+use substrate_subxt::{Event, EventsDecoder, Raw, RawEvent};
+use sunshine_client_utils::{Client as _, Result};
+use thiserror::Error;
+
+type Header = <Runtime as System>::Header;
+type Hash = <Runtime as System>::Hash;
+type BlockNumber = <Runtime as System>::BlockNumber;
+
+macro_rules! sunshine_events {
+    ($($variant:ident($event:ty),)*) => {
+        /// An event of the sunshine chain.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub enum SunshineEvent {
+            $($variant($event),)*
+            /// An event without a variant or that failed to decode.
+            Other(RawEvent),
+        }
+
+        impl SunshineEvent {
+            /// Decodes `raw` into the variant of its module and name. An event
+            /// that fails to decode is logged and left undecoded, so it
+            /// doesn't fail the block.
+            pub fn decode(raw: RawEvent) -> Self {
+                $(
+                    if raw.module == <$event as Event<Runtime>>::MODULE
+                        && raw.variant == <$event as Event<Runtime>>::EVENT
+                    {
+                        return match Decode::decode(&mut &raw.data[..]) {
+                            Ok(event) => Self::$variant(event),
+                            Err(err) => {
+                                log::warn!(
+                                    "failed to decode {}::{}: {:?}",
+                                    raw.module,
+                                    raw.variant,
+                                    err
+                                );
+                                Self::Other(raw)
+                            }
+                        };
+                    }
+                )*
+                Self::Other(raw)
+            }
+
+            /// Returns the module and the name of the event.
+            pub fn name(&self) -> (&str, &str) {
+                match self {
+                    $(
+                        Self::$variant(_) => (
+                            <$event as Event<Runtime>>::MODULE,
+                            <$event as Event<Runtime>>::EVENT,
+                        ),
+                    )*
+                    Self::Other(raw) => (&raw.module, &raw.variant),
+                }
+            }
+        }
+
+        $(
+            impl From<$event> for SunshineEvent {
+                fn from(event: $event) -> Self {
+                    Self::$variant(event)
+                }
+            }
+        )*
+    };
+}
+
+sunshine_events! {
+    BountyPosted(BountyPostedEvent<Runtime>),
+    BountyRaiseContribution(BountyRaiseContributionEvent<Runtime>),
+    BountySubmissionPosted(BountySubmissionPostedEvent<Runtime>),
+    BountyPaymentExecuted(BountyPaymentExecutedEvent<Runtime>),
+    EscrowPosted(escrow::EscrowPostedEvent<Runtime>),
+    EscrowContribution(escrow::EscrowContributionEvent<Runtime>),
+    MilestoneSubmitted(escrow::MilestoneSubmittedEvent<Runtime>),
+    MilestonePaid(escrow::MilestonePaidEvent<Runtime>),
+    MilestoneRejected(escrow::MilestoneRejectedEvent<Runtime>),
+    MilestoneApproved(escrow::MilestoneApprovedEvent<Runtime>),
+    EscrowCompleted(escrow::EscrowCompletedEvent<Runtime>),
+    EscrowRefunded(escrow::EscrowRefundedEvent<Runtime>),
+    EscrowExpired(escrow::EscrowExpiredEvent<Runtime>),
+    DisputeOpened(escrow::DisputeOpenedEvent<Runtime>),
+    DisputeVoted(escrow::DisputeVotedEvent<Runtime>),
+    DisputeResolved(escrow::DisputeResolvedEvent<Runtime>),
+    ApproversSet(escrow::ApproversSetEvent<Runtime>),
+    VestingSet(escrow::VestingSetEvent<Runtime>),
+    VestingStarted(escrow::VestingStartedEvent<Runtime>),
+    Vested(escrow::VestedEvent<Runtime>),
+    DisputeDismissed(escrow::DisputeDismissedEvent<Runtime>),
+    AccountCreated(AccountCreatedEvent<Runtime>),
+    IdentityChanged(IdentityChangedEvent<Runtime>),
+    PasswordChanged(PasswordChangedEvent<Runtime>),
+    Minted(MintedEvent<Runtime>),
+    Endowed(EndowedEvent<Runtime>),
+    Transfer(TransferEvent<Runtime>),
+}
+
+/// A [`SunshineEvent`] with the block and extrinsic that emitted it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventRecord {
+    pub block_number: BlockNumber,
+    pub block_hash: Hash,
+    /// Index of the extrinsic in the block, `None` for events emitted when
+    /// the block is initialized or finalized.
+    pub extrinsic_index: Option<u32>,
+    pub event: SunshineEvent,
+}
+
+/// The events of a block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockEvents {
+    pub block_number: BlockNumber,
+    pub block_hash: Hash,
+    pub events: Vec<EventRecord>,
+}
+
+#[derive(Debug, Error)]
+#[error("Block {0} not found.")]
+pub struct BlockNotFound(pub BlockNumber);
+
+/// Stream of the events of new blocks.
+pub struct EventStream {
+    client: substrate_subxt::Client<Runtime>,
+    headers: Subscription<Header>,
+    decoder: EventsDecoder<Runtime>,
+    /// Next block to return, `None` until the first header arrives.
+    next_block: Option<BlockNumber>,
+    /// Number of the latest header.
+    head: BlockNumber,
+    pending: VecDeque<EventRecord>,
+}
+
+impl EventStream {
+    /// Returns the next event. When a block fails to be fetched or decoded
+    /// the error is returned and the block is retried by the next call.
+    pub async fn next(&mut self) -> Result<EventRecord> {
+        loop {
+            if let Some(record) = self.pending.pop_front() {
+                return Ok(record);
+            }
+            self.pending = self.next_block().await?.events.into();
+        }
+    }
+
+    /// Returns the events of the next block, including blocks without
+    /// events. Blocks are returned in order without gaps, even if the node
+    /// skips headers, like when finalizing several blocks at once.
+    pub async fn next_block(&mut self) -> Result<BlockEvents> {
+        loop {
+            match self.next_block {
+                Some(block_number) if block_number <= self.head => {
+                    let block_hash = self
+                        .client
+                        .block_hash(Some(block_number.into()))
+                        .await?
+                        .ok_or(BlockNotFound(block_number))?;
+                    let data = self.fetch_events(block_hash).await?;
+                    let events = self.decode_events(block_number, block_hash, data)?;
+                    self.next_block = Some(block_number + 1);
+                    return Ok(BlockEvents {
+                        block_number,
+                        block_hash,
+                        events,
+                    });
+                }
+                _ => {
+                    let header = self.headers.next().await;
+                    self.head = *header.number();
+                    self.next_block.get_or_insert(self.head);
+                }
+            }
+        }
+    }
+
+    async fn fetch_events(&self, block_hash: Hash) -> Result<Option<StorageData>> {
+        let params = Params::Array(vec![
+            to_json_value(events_key())?,
+            to_json_value(block_hash)?,
+        ]);
+        Ok(self
+            .client
+            .rpc_client()
+            .request("state_getStorage", params)
+            .await?)
+    }
+
+    fn decode_events(
+        &self,
+        block_number: BlockNumber,
+        block_hash: Hash,
+        data: Option<StorageData>,
+    ) -> Result<Vec<EventRecord>> {
+        let data = match data {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+        let mut records = Vec::new();
+        for (phase, raw) in self.decoder.decode_events(&mut &data.0[..])? {
+            let event = match raw {
+                Raw::Event(event) => event,
+                Raw::Error(_) => continue,
+            };
+            let extrinsic_index = match phase {
+                Phase::ApplyExtrinsic(index) => Some(index),
+                _ => None,
+            };
+            records.push(EventRecord {
+                block_number,
+                block_hash,
+                extrinsic_index,
+                event: SunshineEvent::decode(event),
+            });
+        }
+        Ok(records)
+    }
+}
+
+/// Storage key of `System.Events`.
+fn events_key() -> StorageKey {
+    let mut key = twox_128(b"System").to_vec();
+    key.extend_from_slice(&twox_128(b"Events"));
+    StorageKey(key)
+}
+
+/// Registers the sizes of the event arguments of the substrate modules that
+/// don't have a client module. Blocks with events of types that aren't
+/// registered fail to decode.
+fn register_runtime_types(decoder: &mut EventsDecoder<Runtime>) {
+    decoder.register_type_size::<u32>("ProposalIndex");
+    decoder.register_type_size::<u32>("MemberCount");
+    decoder.register_type_size::<u32>("ReferendumIndex");
+    decoder.register_type_size::<u32>("PropIndex");
+    decoder.register_type_size::<u32>("SessionIndex");
+    decoder.register_type_size::<u32>("BountyIndex");
+    decoder.register_type_size::<u8>("VoteThreshold");
+    decoder.register_type_size::<(BlockNumber, u32)>("TaskAddress<BlockNumber>");
+    // Grandpa authorities are ed25519 keys with their weight.
+    decoder.register_type_size::<Vec<([u8; 32], u64)>>("AuthorityList");
+    decoder.register_type_size::<Perbill>("Perbill");
+    decoder.register_type_size::<[u8; 16]>("Kind");
+    decoder.register_type_size::<Vec<u8>>("OpaqueTimeSlot");
+}
+
+#[async_trait]
+pub trait EventClient {
+    /// Subscribes to the events of new blocks. With `finalized` only the
+    /// events of finalized blocks are returned, which won't be reverted.
+    async fn subscribe_sunshine_events(&self, finalized: bool) -> Result<EventStream>;
+
+    /// Subscribes to the events of the blocks starting at `block_number`,
+    /// catching up with the chain before following new blocks.
+    async fn subscribe_sunshine_events_from(
+        &self,
+        finalized: bool,
+        block_number: BlockNumber,
+    ) -> Result<EventStream>;
+}
+
+#[async_trait]
+impl EventClient for Client {
+    async fn subscribe_sunshine_events(&self, finalized: bool) -> Result<EventStream> {
+        subscribe(self, finalized, None).await
+    }
+
+    async fn subscribe_sunshine_events_from(
+        &self,
+        finalized: bool,
+        block_number: BlockNumber,
+    ) -> Result<EventStream> {
+        subscribe(self, finalized, Some(block_number)).await
+    }
+}
+
+async fn subscribe(
+    client: &Client,
+    finalized: bool,
+    next_block: Option<BlockNumber>,
+) -> Result<EventStream> {
+    let client = client.chain_client().clone();
+    let headers = if finalized {
+        client.subscribe_finalized_blocks().await?
+    } else {
+        client.subscribe_blocks().await?
+    };
+    let mut decoder = EventsDecoder::<Runtime>::new(client.metadata().clone());
+    decoder.with_system();
+    decoder.with_balances();
+    decoder.with_bounty();
+    decoder.with_escrow();
+    decoder.with_faucet();
+    decoder.with_identity();
+    decoder.with_indices();
+    decoder.with_proxy();
+    decoder.with_utility();
+    register_runtime_types(&mut decoder);
+    Ok(EventStream {
+        client,
+        headers,
+        decoder,
+        next_block,
+        head: 0,
+        pending: Default::default(),
+    })
+}
@@ -0,0 +1,179 @@
+//! Client for the rate limited faucet.
+use crate::escrow::EventNotFound;
+use crate::{AccountId, Client, Runtime};
+use async_trait::async_trait;
+use parity_scale_codec::{Decode, Encode};
+use substrate_subxt::balances::{Balances, BalancesEventsDecoder};
+use substrate_subxt::sp_runtime::traits::Header;
+use substrate_subxt::system::{System, SystemEventsDecoder};
+use substrate_subxt::{module, Call, Event, Store, UncheckedExtrinsic};
+use sunshine_client_utils::{Client as _, Result};
+use sunshine_faucet_pallet::{leading_zeros, pow_hash};
+use thiserror::Error;
+
+pub type BalanceOf<T> = <T as Balances>::Balance;
+
+#[module]
+pub trait Faucet: Balances {}
+
+// ------------------------------------------------------------------------------
+// Storage
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct NextMintStore<'a, T: Faucet> {
+    #[store(returns = <T as System>::BlockNumber)]
+    pub account: &'a <T as System>::AccountId,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct PeriodStartStore<T: Faucet> {
+    #[store(returns = <T as System>::BlockNumber)]
+    pub _runtime: core::marker::PhantomData<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct PeriodMintsStore<T: Faucet> {
+    #[store(returns = u32)]
+    pub _runtime: core::marker::PhantomData<T>,
+}
+
+// ------------------------------------------------------------------------------
+// Calls
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct MintCall<'a, T: Faucet> {
+    pub account: &'a <T as System>::AccountId,
+    pub nonce: u64,
+}
+
+// ------------------------------------------------------------------------------
+// Events
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct MintedEvent<T: Faucet> {
+    pub account: <T as System>::AccountId,
+    pub amount: BalanceOf<T>,
+}
+
+// ------------------------------------------------------------------------------
+// Client
+// ------------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+#[error("Header not found.")]
+pub struct HeaderNotFound;
+
+/// When an account may mint again.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintStatus {
+    /// Current block.
+    pub now: u32,
+    /// First block the account may mint at.
+    pub next_mint: u32,
+    /// Mints left in the current mint period, across all accounts.
+    pub mints_left: u32,
+    /// First block of the next mint period, when `mints_left` resets.
+    pub next_period: u32,
+}
+
+impl MintStatus {
+    /// First block the account may mint at, given the global limit.
+    pub fn available_at(&self) -> u32 {
+        if self.mints_left > 0 {
+            self.next_mint
+        } else {
+            self.next_mint.max(self.next_period)
+        }
+    }
+
+    pub fn can_mint(&self) -> bool {
+        self.now >= self.available_at()
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("No proof of work found for difficulty {0}.")]
+pub struct ProofOfWorkNotFound(u32);
+
+/// Number of nonces tried before giving up on the proof of work.
+const MAX_POW_ATTEMPTS: u64 = 1 << 32;
+
+/// Finds a nonce meeting the difficulty of the faucet pallet.
+pub fn solve_pow(
+    account: &AccountId,
+    next_mint: u32,
+    difficulty: u32,
+) -> core::result::Result<u64, ProofOfWorkNotFound> {
+    (0..MAX_POW_ATTEMPTS)
+        .find(|nonce| leading_zeros(&pow_hash(account, next_mint, *nonce)) >= difficulty)
+        .ok_or(ProofOfWorkNotFound(difficulty))
+}
+
+#[async_trait]
+pub trait FaucetClient {
+    /// Mints funds to the account of the current signer.
+    async fn mint(&self) -> Result<MintedEvent<Runtime>>;
+
+    /// Returns when `account` may mint again.
+    async fn mint_status(&self, account: &AccountId) -> Result<MintStatus>;
+}
+
+#[async_trait]
+impl FaucetClient for Client {
+    async fn mint(&self) -> Result<MintedEvent<Runtime>> {
+        let account = self.chain_signer()?.account_id().clone();
+        let client = self.chain_client();
+        let difficulty = client
+            .metadata()
+            .module("Faucet")?
+            .constant("Difficulty")?
+            .value::<u32>()?;
+        let next_mint = client.next_mint(&account, None).await?;
+        let nonce = solve_pow(&account, next_mint, difficulty)?;
+        let call = client.encode(MintCall::<Runtime> {
+            account: &account,
+            nonce,
+        })?;
+        let extrinsic = UncheckedExtrinsic::<Runtime>::new_unsigned(call);
+        let decoder = client.events_decoder::<MintCall<Runtime>>();
+        Ok(client
+            .submit_and_watch_extrinsic(extrinsic, decoder)
+            .await?
+            .minted()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn mint_status(&self, account: &AccountId) -> Result<MintStatus> {
+        let client = self.chain_client();
+        let period = client
+            .metadata()
+            .module("Faucet")?
+            .constant("MintPeriod")?
+            .value::<u32>()?;
+        let mints_per_period = client
+            .metadata()
+            .module("Faucet")?
+            .constant("MintsPerPeriod")?
+            .value::<u32>()?;
+        let now = *client
+            .header(None::<<Runtime as System>::Hash>)
+            .await?
+            .ok_or(HeaderNotFound)?
+            .number();
+        let mut period_mints = client.period_mints(None).await?;
+        let mut next_period = client.period_start(None).await?.saturating_add(period);
+        if period_mints == 0 || now >= next_period {
+            period_mints = 0;
+            next_period = now.saturating_add(period);
+        }
+        Ok(MintStatus {
+            now,
+            next_mint: client.next_mint(account, None).await?,
+            mints_left: mints_per_period.saturating_sub(period_mints),
+            next_period,
+        })
+    }
+}
@@ -0,0 +1,50 @@
+//! Fee estimation for extrinsics before they are submitted.
+use crate::runtime_api::state_call;
+use crate::{Address, Client, Runtime};
+use async_trait::async_trait;
+use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
+use parity_scale_codec::Encode;
+use substrate_subxt::balances::{Balances, TransferCall};
+use substrate_subxt::Call;
+use sunshine_client_utils::{Client as _, Result};
+
+pub type BalanceOf<T> = <T as Balances>::Balance;
+
+#[async_trait]
+pub trait FeeClient {
+    /// Returns the weight, class and fee of `call` signed by the current
+    /// signer, without submitting it.
+    async fn query_fee<C: Call<Runtime> + Send + Sync>(
+        &self,
+        call: C,
+    ) -> Result<RuntimeDispatchInfo<BalanceOf<Runtime>>>;
+
+    /// Returns the fee of transferring `amount` to `to`.
+    async fn estimate_transfer_fee(
+        &self,
+        to: &Address,
+        amount: BalanceOf<Runtime>,
+    ) -> Result<BalanceOf<Runtime>>;
+}
+
+#[async_trait]
+impl FeeClient for Client {
+    async fn query_fee<C: Call<Runtime> + Send + Sync>(
+        &self,
+        call: C,
+    ) -> Result<RuntimeDispatchInfo<BalanceOf<Runtime>>> {
+        let signer = self.chain_signer()?;
+        let extrinsic = self.chain_client().create_signed(call, signer).await?;
+        let len = extrinsic.encoded_size() as u32;
+        state_call(self, "TransactionPaymentApi_query_info", (extrinsic, len)).await
+    }
+
+    async fn estimate_transfer_fee(
+        &self,
+        to: &Address,
+        amount: BalanceOf<Runtime>,
+    ) -> Result<BalanceOf<Runtime>> {
+        let call = TransferCall::<Runtime> { to, amount };
+        Ok(self.query_fee(call).await?.partial_fee)
+    }
+}
@@ -0,0 +1,31 @@
+//! Resolution of github handles to uids.
+//!
+//! Github handles are proven by claims in the offchain claim chain of a uid,
+//! which the node can't read, so the client resolves them. The devices of the
+//! uid are read with the `IdentityApi` runtime api.
+use crate::runtime_api::state_call;
+use crate::{AccountId, Client, Uid};
+use async_trait::async_trait;
+use sunshine_client_utils::Result;
+use sunshine_identity_client::{resolve, Identifier, Service};
+
+#[async_trait]
+pub trait GithubClient {
+    /// Returns the uid that proved the github `handle`.
+    async fn github_uid(&self, handle: &str) -> Result<Uid>;
+
+    /// Returns the devices of `uid`.
+    async fn devices(&self, uid: Uid) -> Result<Vec<AccountId>>;
+}
+
+#[async_trait]
+impl GithubClient for Client {
+    async fn github_uid(&self, handle: &str) -> Result<Uid> {
+        let identifier = Identifier::Service(Service::Github(handle.to_string()));
+        Ok(resolve(self, Some(identifier)).await?)
+    }
+
+    async fn devices(&self, uid: Uid) -> Result<Vec<AccountId>> {
+        state_call(self, "IdentityApi_devices", uid).await
+    }
+}
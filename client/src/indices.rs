@@ -0,0 +1,180 @@
+//! Client for short account indices.
+use crate::escrow::EventNotFound;
+use crate::github::GithubClient;
+use crate::{AccountId, Address, Client, Runtime};
+use async_trait::async_trait;
+use frame_support::Parameter;
+use parity_scale_codec::{Decode, Encode};
+use std::str::FromStr;
+use substrate_subxt::balances::{
+    Balances, BalancesEventsDecoder, TransferCallExt, TransferEvent, TransferEventExt,
+};
+use substrate_subxt::sp_runtime::traits::{AtLeast32Bit, Member};
+use substrate_subxt::system::{System, SystemEventsDecoder};
+use substrate_subxt::{module, Call, Event, Store};
+use sunshine_client_utils::{Client as _, Result};
+use thiserror::Error;
+
+pub type BalanceOf<T> = <T as Balances>::Balance;
+
+#[module]
+pub trait Indices: System + Balances {
+    type AccountIndex: Parameter + Member + AtLeast32Bit + Default + Copy;
+}
+
+// ------------------------------------------------------------------------------
+// Storage
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct AccountsStore<T: Indices> {
+    #[store(returns = Option<(<T as System>::AccountId, BalanceOf<T>, bool)>)]
+    pub index: T::AccountIndex,
+}
+
+// ------------------------------------------------------------------------------
+// Calls
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct ClaimCall<T: Indices> {
+    pub index: T::AccountIndex,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct FreeCall<T: Indices> {
+    pub index: T::AccountIndex,
+}
+
+// ------------------------------------------------------------------------------
+// Events
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct IndexAssignedEvent<T: Indices> {
+    pub account: <T as System>::AccountId,
+    pub index: T::AccountIndex,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct IndexFreedEvent<T: Indices> {
+    pub index: T::AccountIndex,
+}
+
+// ------------------------------------------------------------------------------
+// Client
+// ------------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+#[error("Expected an account index, an ss58 account id or a @github handle.")]
+pub struct InvalidRecipient;
+
+#[derive(Debug, Error)]
+#[error("The uid doesn't have any devices.")]
+pub struct NoDevices;
+
+/// Recipient of a transfer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Recipient {
+    /// Account index like `42`.
+    Index(u32),
+    /// Ss58 encoded account id.
+    Account(AccountId),
+    /// Github handle like `@dvc94ch`, proven in the claim chain of a uid.
+    Github(String),
+}
+
+impl FromStr for Recipient {
+    type Err = InvalidRecipient;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(handle) = s.strip_prefix('@') {
+            Ok(Self::Github(handle.to_string()))
+        } else if let Ok(index) = s.parse() {
+            Ok(Self::Index(index))
+        } else {
+            Ok(Self::Account(s.parse().map_err(|_| InvalidRecipient)?))
+        }
+    }
+}
+
+#[async_trait]
+pub trait IndicesClient {
+    /// Claims the unused account index `index` for the current signer.
+    async fn claim_index(&self, index: u32) -> Result<IndexAssignedEvent<Runtime>>;
+
+    /// Frees the account index `index` of the current signer.
+    async fn free_index(&self, index: u32) -> Result<IndexFreedEvent<Runtime>>;
+
+    /// Returns the account `index` is assigned to.
+    async fn index_account(&self, index: u32) -> Result<Option<AccountId>>;
+
+    /// Returns the address of `recipient`. Github handles resolve to the
+    /// first device of the uid that proved the handle.
+    async fn lookup(&self, recipient: &Recipient) -> Result<Address>;
+
+    /// Transfers `amount` to `recipient`.
+    async fn transfer_to(
+        &self,
+        recipient: &Recipient,
+        amount: BalanceOf<Runtime>,
+    ) -> Result<TransferEvent<Runtime>>;
+}
+
+#[async_trait]
+impl IndicesClient for Client {
+    async fn claim_index(&self, index: u32) -> Result<IndexAssignedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .claim_and_watch(signer, index)
+            .await?
+            .index_assigned()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn free_index(&self, index: u32) -> Result<IndexFreedEvent<Runtime>> {
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .free_and_watch(signer, index)
+            .await?
+            .index_freed()?
+            .ok_or(EventNotFound)?)
+    }
+
+    async fn index_account(&self, index: u32) -> Result<Option<AccountId>> {
+        Ok(self
+            .chain_client()
+            .accounts(index, None)
+            .await?
+            .map(|(account, _, _)| account))
+    }
+
+    async fn lookup(&self, recipient: &Recipient) -> Result<Address> {
+        Ok(match recipient {
+            Recipient::Index(index) => Address::Index(*index),
+            Recipient::Account(account) => Address::Id(account.clone()),
+            Recipient::Github(handle) => {
+                let uid = self.github_uid(handle).await?;
+                let devices = self.devices(uid).await?;
+                Address::Id(devices.into_iter().next().ok_or(NoDevices)?)
+            }
+        })
+    }
+
+    async fn transfer_to(
+        &self,
+        recipient: &Recipient,
+        amount: BalanceOf<Runtime>,
+    ) -> Result<TransferEvent<Runtime>> {
+        let to = self.lookup(recipient).await?;
+        let signer = self.chain_signer()?;
+        Ok(self
+            .chain_client()
+            .transfer_and_watch(signer, &to, amount)
+            .await?
+            .transfer()?
+            .ok_or(EventNotFound)?)
+    }
+}
@@ -1,96 +1,170 @@
-use libipld::cache::IpldCache;
+use body::BountyBody;
+use cache::{CacheConfig, CacheMetrics, CacheStats, TieredCache};
 use libipld::cbor::DagCborCodec;
+use libipld::cid::Cid;
 use libipld::derive_cache;
 use libipld::store::Store;
 use sc_service::{Configuration, RpcHandlers, TaskManager};
+use std::collections::HashSet;
 use std::ops::Deref;
-use substrate_subxt::balances::{AccountData, Balances};
-use substrate_subxt::sp_runtime::traits::{IdentifyAccount, Verify};
+use std::sync::{Arc, Mutex};
+use substrate_subxt::balances::Balances;
+use substrate_subxt::sp_runtime::traits::StaticLookup;
 use substrate_subxt::system::System;
-use substrate_subxt::{extrinsic, sp_core, sp_runtime};
+use substrate_subxt::{extrinsic, sp_runtime};
 use sunshine_bounty_client::bounty::Bounty;
-use sunshine_client_utils::codec::hasher::BLAKE2B_256;
-use sunshine_client_utils::codec::Cid;
 use sunshine_client_utils::crypto::keychain::KeyType;
 use sunshine_client_utils::crypto::sr25519;
 use sunshine_client_utils::{
     sc_service, ChainSpecError, GenericClient, Network, Node as NodeT, OffchainStore,
 };
-use sunshine_faucet_client::Faucet;
 use sunshine_identity_client::{Claim, Identity};
+use sunshine_runtime as runtime;
+
+pub mod body;
+pub mod cache;
+pub mod escrow;
+pub mod events;
+pub mod faucet;
+pub mod fee;
+pub mod github;
+pub mod indices;
+pub mod proxy;
+mod runtime_api;
+#[cfg(test)]
+mod tests;
+pub mod utility;
 
 pub use sunshine_bounty_client::*;
 pub use sunshine_client_utils as client;
-pub use sunshine_faucet_client as faucet;
 pub use sunshine_identity_client as identity;
 
-pub type AccountId = <<sp_runtime::MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
-pub type Uid = u32;
+pub type AccountId = <runtime::Runtime as frame_system::Trait>::AccountId;
+pub type AccountIndex = <runtime::Runtime as pallet_indices::Trait>::AccountIndex;
+pub type Address = <<runtime::Runtime as frame_system::Trait>::Lookup as StaticLookup>::Source;
+pub type Uid = <runtime::Runtime as sunshine_identity_pallet::Trait>::Uid;
 
+/// The client side of `sunshine_runtime::Runtime`.
+///
+/// The associated types are taken from the runtime, so they can't drift
+/// apart from the types the runtime encodes storage, calls and events with.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Runtime;
 
 impl System for Runtime {
-    type Index = u32;
-    type BlockNumber = u32;
-    type Hash = sp_core::H256;
-    type Hashing = sp_runtime::traits::BlakeTwo256;
+    type Index = <runtime::Runtime as frame_system::Trait>::Index;
+    type BlockNumber = <runtime::Runtime as frame_system::Trait>::BlockNumber;
+    type Hash = <runtime::Runtime as frame_system::Trait>::Hash;
+    type Hashing = <runtime::Runtime as frame_system::Trait>::Hashing;
     type AccountId = AccountId;
-    type Address = AccountId;
-    type Header = sp_runtime::generic::Header<Self::BlockNumber, Self::Hashing>;
+    type Address = Address;
+    type Header = <runtime::Runtime as frame_system::Trait>::Header;
     type Extrinsic = sp_runtime::OpaqueExtrinsic;
-    type AccountData = ();
+    type AccountData = <runtime::Runtime as frame_system::Trait>::AccountData;
 }
 
 impl Balances for Runtime {
-    type Balance = u128;
+    type Balance = <runtime::Runtime as pallet_balances::Trait>::Balance;
 }
 
-impl Faucet for Runtime {}
+impl faucet::Faucet for Runtime {}
+
+impl indices::Indices for Runtime {
+    type AccountIndex = AccountIndex;
+}
+
+impl proxy::Proxy for Runtime {
+    type ProxyType = <runtime::Runtime as pallet_proxy::Trait>::ProxyType;
+}
+
+impl utility::Utility for Runtime {}
 
 impl Identity for Runtime {
     type Uid = Uid;
-    type Cid = Cid;
-    type Mask = [u8; 32];
-    type Gen = u16;
-    type IdAccountData = AccountData<<Self as Balances>::Balance>;
+    type Cid = <runtime::Runtime as sunshine_identity_pallet::Trait>::Cid;
+    type Mask = <runtime::Runtime as sunshine_identity_pallet::Trait>::Mask;
+    type Gen = <runtime::Runtime as sunshine_identity_pallet::Trait>::Gen;
+    type IdAccountData = <runtime::Runtime as sunshine_identity_pallet::Trait>::AccountData;
 }
 
 impl Bounty for Runtime {
-    type IpfsReference = Cid;
-    type BountyId = u64;
-    type BountyPost = GithubIssue;
-    type SubmissionId = u64;
-    type BountySubmission = GithubIssue;
+    type IpfsReference = <runtime::Runtime as sunshine_bounty::Trait>::IpfsReference;
+    type BountyId = <runtime::Runtime as sunshine_bounty::Trait>::BountyId;
+    type BountyPost = BountyBody;
+    type SubmissionId = <runtime::Runtime as sunshine_bounty::Trait>::SubmissionId;
+    type BountySubmission = BountyBody;
+}
+
+impl escrow::Escrow for Runtime {
+    type IpfsReference = <runtime::Runtime as sunshine_escrow_pallet::Trait>::IpfsReference;
+    type EscrowId = <runtime::Runtime as sunshine_escrow_pallet::Trait>::EscrowId;
+    type SubmissionId = <runtime::Runtime as sunshine_escrow_pallet::Trait>::SubmissionId;
+    type Uid = <runtime::Runtime as sunshine_escrow_pallet::Trait>::Uid;
 }
 
 impl substrate_subxt::Runtime for Runtime {
-    type Signature = sp_runtime::MultiSignature;
+    type Signature = runtime::Signature;
     type Extra = extrinsic::DefaultExtra<Self>;
 }
 
 pub struct OffchainClient<S> {
     store: S,
-    claims: IpldCache<S, DagCborCodec, Claim>,
-    bounties: IpldCache<S, DagCborCodec, GithubIssue>,
+    claims: TieredCache<S, Claim>,
+    bounties: TieredCache<S, BountyBody>,
+    metrics: Arc<CacheMetrics>,
+    pins: Mutex<HashSet<Cid>>,
 }
 
 impl<S: Store> OffchainClient<S> {
     pub fn new(store: S) -> Self {
+        Self::with_config(store, &CacheConfig::default())
+    }
+
+    pub fn with_config(store: S, config: &CacheConfig) -> Self {
+        let metrics = Arc::new(CacheMetrics::default());
         Self {
-            claims: IpldCache::new(store.clone(), DagCborCodec, BLAKE2B_256, 64),
-            bounties: IpldCache::new(store.clone(), DagCborCodec, BLAKE2B_256, 64),
+            claims: TieredCache::new(store.clone(), config, "claims", metrics.clone()),
+            bounties: TieredCache::new(store.clone(), config, "bounties", metrics.clone()),
             store,
+            metrics,
+            pins: Default::default(),
+        }
+    }
+
+    /// Returns the hits and misses of the caches.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.metrics.stats()
+    }
+
+    /// Pins `cids` so the store doesn't garbage collect them, and unpins the
+    /// cids pinned by a previous call that aren't in `cids`.
+    pub async fn set_pins(&self, cids: HashSet<Cid>) -> libipld::error::Result<()> {
+        let pins = self.pins.lock().unwrap().clone();
+        for cid in cids.difference(&pins) {
+            self.store.alias(&pin_alias(cid), Some(cid)).await?;
         }
+        for cid in pins.difference(&cids) {
+            self.store.alias(&pin_alias(cid), None).await?;
+        }
+        *self.pins.lock().unwrap() = cids;
+        Ok(())
     }
 }
 
+fn pin_alias(cid: &Cid) -> Vec<u8> {
+    let mut alias = b"sunshine-pin:".to_vec();
+    alias.extend_from_slice(&cid.to_bytes());
+    alias
+}
+
 derive_cache!(OffchainClient, claims, DagCborCodec, Claim);
-derive_cache!(OffchainClient, bounties, DagCborCodec, GithubIssue);
+derive_cache!(OffchainClient, bounties, DagCborCodec, BountyBody);
 
+/// The client creates its offchain client from the store, so the caches are
+/// configured with the environment, see [`CacheConfig::from_env`].
 impl<S: Store> From<S> for OffchainClient<S> {
     fn from(store: S) -> Self {
-        Self::new(store)
+        Self::with_config(store, &CacheConfig::from_env())
     }
 }
 
@@ -142,6 +216,19 @@ impl NodeT for Node {
         Ok(sunshine_node::new_light(config)?)
     }
 
+    /// Mock nodes seal a block for every transaction, so tests don't have to
+    /// wait for aura slots.
+    #[cfg(feature = "mock")]
+    fn new_full(
+        config: Configuration,
+    ) -> Result<(TaskManager, RpcHandlers, Network<Self>), sc_service::Error> {
+        Ok(sunshine_node::new_dev(
+            config,
+            sunshine_node::Sealing::Instant,
+        )?)
+    }
+
+    #[cfg(not(feature = "mock"))]
     fn new_full(
         config: Configuration,
     ) -> Result<(TaskManager, RpcHandlers, Network<Self>), sc_service::Error> {
@@ -0,0 +1,168 @@
+//! Client for proxy accounts.
+use crate::escrow::EventNotFound;
+use crate::{AccountId, Client, Runtime};
+use async_trait::async_trait;
+use core::marker::PhantomData;
+use frame_support::Parameter;
+use parity_scale_codec::{Decode, Encode};
+use substrate_subxt::balances::{Balances, BalancesEventsDecoder};
+use substrate_subxt::sp_runtime::traits::Member;
+use substrate_subxt::sp_runtime::{DispatchError, DispatchResult};
+use substrate_subxt::system::{System, SystemEventsDecoder};
+use substrate_subxt::{module, Call, Encoded, Event, ExtrinsicSuccess, Store};
+use sunshine_client_utils::{Client as _, Result};
+use thiserror::Error;
+
+pub type BalanceOf<T> = <T as Balances>::Balance;
+
+#[module]
+pub trait Proxy: System + Balances {
+    type ProxyType: Parameter + Member + Default + Copy;
+}
+
+pub use sunshine_runtime::ProxyType;
+
+#[derive(Debug, Error)]
+#[error("Expected a proxy type of `any` or `bounty-approver`.")]
+pub struct InvalidProxyType;
+
+/// Parses the name of a proxy type like `bounty-approver`.
+pub fn parse_proxy_type(s: &str) -> std::result::Result<ProxyType, InvalidProxyType> {
+    match s {
+        "any" => Ok(ProxyType::Any),
+        "bounty-approver" => Ok(ProxyType::BountyApprover),
+        _ => Err(InvalidProxyType),
+    }
+}
+
+/// Name of a proxy type, the inverse of `parse_proxy_type`.
+pub fn proxy_type_name(proxy_type: ProxyType) -> &'static str {
+    match proxy_type {
+        ProxyType::Any => "any",
+        ProxyType::BountyApprover => "bounty-approver",
+    }
+}
+
+/// A proxy of an account.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber> {
+    pub delegate: AccountId,
+    pub proxy_type: ProxyType,
+    pub delay: BlockNumber,
+}
+
+pub type ProxyDefinitionOf<T> =
+    ProxyDefinition<<T as System>::AccountId, <T as Proxy>::ProxyType, <T as System>::BlockNumber>;
+
+// ------------------------------------------------------------------------------
+// Storage
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Store, Encode)]
+pub struct ProxiesStore<'a, T: Proxy> {
+    #[store(returns = (Vec<ProxyDefinitionOf<T>>, BalanceOf<T>))]
+    pub account: &'a <T as System>::AccountId,
+}
+
+// ------------------------------------------------------------------------------
+// Calls
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct AddProxyCall<'a, T: Proxy> {
+    pub delegate: &'a <T as System>::AccountId,
+    pub proxy_type: T::ProxyType,
+    pub delay: <T as System>::BlockNumber,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct RemoveProxyCall<'a, T: Proxy> {
+    pub delegate: &'a <T as System>::AccountId,
+    pub proxy_type: T::ProxyType,
+    pub delay: <T as System>::BlockNumber,
+}
+
+/// Dispatches `call` on behalf of `real`.
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct ProxyCall<'a, T: Proxy> {
+    pub real: &'a <T as System>::AccountId,
+    pub force_proxy_type: Option<T::ProxyType>,
+    pub call: &'a Encoded,
+}
+
+// ------------------------------------------------------------------------------
+// Events
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct ProxyExecutedEvent<T: Proxy> {
+    pub result: DispatchResult,
+    pub _runtime: PhantomData<T>,
+}
+
+// ------------------------------------------------------------------------------
+// Client
+// ------------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+#[error("Proxied call failed: {0:?}")]
+pub struct ProxiedCallFailed(pub DispatchError);
+
+#[async_trait]
+pub trait ProxyClient {
+    /// Allows `delegate` to make calls of `proxy_type` on behalf of the
+    /// current signer, reserving the proxy deposit.
+    async fn add_proxy(&self, delegate: &AccountId, proxy_type: ProxyType) -> Result<()>;
+
+    /// Removes a proxy of the current signer and unreserves its deposit.
+    async fn remove_proxy(&self, delegate: &AccountId, proxy_type: ProxyType) -> Result<()>;
+
+    /// Returns the proxies of `account`.
+    async fn proxies(&self, account: &AccountId) -> Result<Vec<ProxyDefinitionOf<Runtime>>>;
+
+    /// Dispatches the encoded `call` on behalf of `real`, which has to have
+    /// added the current signer as a proxy.
+    async fn proxy_call(
+        &self,
+        real: &AccountId,
+        call: &Encoded,
+    ) -> Result<ExtrinsicSuccess<Runtime>>;
+}
+
+#[async_trait]
+impl ProxyClient for Client {
+    async fn add_proxy(&self, delegate: &AccountId, proxy_type: ProxyType) -> Result<()> {
+        let signer = self.chain_signer()?;
+        self.chain_client()
+            .add_proxy_and_watch(signer, delegate, proxy_type, 0)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_proxy(&self, delegate: &AccountId, proxy_type: ProxyType) -> Result<()> {
+        let signer = self.chain_signer()?;
+        self.chain_client()
+            .remove_proxy_and_watch(signer, delegate, proxy_type, 0)
+            .await?;
+        Ok(())
+    }
+
+    async fn proxies(&self, account: &AccountId) -> Result<Vec<ProxyDefinitionOf<Runtime>>> {
+        Ok(self.chain_client().proxies(account, None).await?.0)
+    }
+
+    async fn proxy_call(
+        &self,
+        real: &AccountId,
+        call: &Encoded,
+    ) -> Result<ExtrinsicSuccess<Runtime>> {
+        let signer = self.chain_signer()?;
+        let result = self
+            .chain_client()
+            .proxy_and_watch(signer, real, None, call)
+            .await?;
+        let event = result.proxy_executed()?.ok_or(EventNotFound)?;
+        event.result.map_err(ProxiedCallFailed)?;
+        Ok(result)
+    }
+}
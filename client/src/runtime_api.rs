@@ -0,0 +1,28 @@
+//! Calls to runtime apis.
+//!
+//! Runtime apis are queried with the `state_call` rpc, so the node doesn't
+//! need a dedicated rpc extension for every api.
+use crate::Client;
+use jsonrpsee::common::{to_value as to_json_value, Params};
+use parity_scale_codec::{Decode, Encode};
+use substrate_subxt::sp_core::Bytes;
+use sunshine_client_utils::{Client as _, Result};
+
+/// Calls `method` of a runtime api, like `TransactionPaymentApi_query_info`,
+/// with the SCALE encoded `args` on the best block.
+pub(crate) async fn state_call<A: Encode, R: Decode>(
+    client: &Client,
+    method: &str,
+    args: A,
+) -> Result<R> {
+    let params = Params::Array(vec![
+        to_json_value(method)?,
+        to_json_value(Bytes(args.encode()))?,
+    ]);
+    let result: Bytes = client
+        .chain_client()
+        .rpc_client()
+        .request("state_call", params)
+        .await?;
+    Ok(Decode::decode(&mut &result[..])?)
+}
@@ -0,0 +1,522 @@
+//! Checks the hand-written storage, call and event types of the client
+//! modules against the metadata of the runtime.
+//!
+//! Calls are encoded with the metadata and decoded as calls of the runtime,
+//! events of the runtime are decoded with the metadata and storage keys are
+//! compared with the keys of the pallets. A field that is added, removed or
+//! reordered in a pallet fails these tests instead of failing on chain.
+use crate::escrow::{self, BalanceOf};
+use crate::proxy::{self, ProxyDefinition, ProxyType};
+use crate::{faucet, indices, runtime, AccountId, Runtime};
+use frame_support::metadata::RuntimeMetadataPrefixed;
+use frame_support::storage::{unhashed, StorageDoubleMap, StorageMap, StorageValue};
+use frame_support::traits::UnfilteredDispatchable;
+use parity_scale_codec::{Decode, Encode};
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use substrate_subxt::sp_runtime::BuildStorage;
+use substrate_subxt::{Call, Encoded, Event, Metadata, Store};
+use sunshine_escrow_pallet as escrow_pallet;
+use sunshine_faucet_pallet as faucet_pallet;
+
+fn metadata() -> Metadata {
+    let encoded = runtime::Runtime::metadata().encode();
+    RuntimeMetadataPrefixed::decode(&mut &encoded[..])
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+fn encode<C: Call<Runtime>>(call: C) -> Encoded {
+    metadata()
+        .module_with_calls(C::MODULE)
+        .unwrap()
+        .call(C::FUNCTION, call)
+        .unwrap()
+}
+
+/// Encodes `call` with the metadata and decodes it as a call of the runtime.
+fn call<C: Call<Runtime>>(call: C) -> runtime::Call {
+    runtime::Call::decode(&mut &encode(call).0[..]).unwrap()
+}
+
+/// Decodes an event of the runtime as `E`, after checking that the metadata
+/// names its module and variant like `E`.
+fn event<E: Event<Runtime>>(event: impl Into<runtime::Event>) -> E {
+    let encoded = event.into().encode();
+    let metadata = metadata();
+    let module = metadata.module_with_events(encoded[0]).unwrap();
+    assert_eq!(module.name(), E::MODULE);
+    assert_eq!(module.event(encoded[1]).unwrap().name, E::EVENT);
+    let mut data = &encoded[2..];
+    let event = E::decode(&mut data).unwrap();
+    assert!(data.is_empty(), "{} has more fields", E::EVENT);
+    event
+}
+
+fn key<S: Store<Runtime>>(store: S) -> Vec<u8> {
+    store.key(&metadata()).unwrap().0
+}
+
+fn alice() -> AccountId {
+    AccountId::from([1; 32])
+}
+
+fn bob() -> AccountId {
+    AccountId::from([2; 32])
+}
+
+fn cid() -> runtime::Cid {
+    Default::default()
+}
+
+type EscrowEvent = escrow_pallet::Event<runtime::Runtime>;
+
+#[test]
+fn escrow_storage() {
+    assert_eq!(
+        key(escrow::BountiesStore::<Runtime> { id: 1 }),
+        escrow_pallet::Bounties::<runtime::Runtime>::hashed_key_for(1)
+    );
+    assert_eq!(
+        key(escrow::ContributionsStore::<Runtime> {
+            id: 1,
+            account: &alice(),
+        }),
+        escrow_pallet::Contributions::<runtime::Runtime>::hashed_key_for(1, alice())
+    );
+    assert_eq!(
+        key(escrow::SubmissionsStore::<Runtime> {
+            id: 1,
+            submission_id: 2,
+        }),
+        escrow_pallet::Submissions::<runtime::Runtime>::hashed_key_for(1, 2)
+    );
+    assert_eq!(
+        key(escrow::DisputesStore::<Runtime> {
+            id: 1,
+            submission_id: 2,
+        }),
+        escrow_pallet::Disputes::<runtime::Runtime>::hashed_key_for(1, 2)
+    );
+    assert_eq!(
+        key(escrow::ApproversStore::<Runtime> { id: 1 }),
+        escrow_pallet::Approvers::<runtime::Runtime>::hashed_key_for(1)
+    );
+    assert_eq!(
+        key(escrow::ApprovalsStore::<Runtime> {
+            id: 1,
+            submission_id: 2,
+        }),
+        escrow_pallet::Approvals::<runtime::Runtime>::hashed_key_for(1, 2)
+    );
+    assert_eq!(
+        key(escrow::VestingPeriodsStore::<Runtime> { id: 1 }),
+        escrow_pallet::VestingPeriods::<runtime::Runtime>::hashed_key_for(1)
+    );
+}
+
+#[test]
+fn escrow_calls() {
+    type EscrowCall = escrow_pallet::Call<runtime::Runtime>;
+    let calls = vec![
+        (
+            call(escrow::PostBountyCall::<Runtime> {
+                description: cid(),
+                milestones: vec![100, 200],
+                deposit: 50,
+                lifetime: 1000,
+            }),
+            EscrowCall::post_bounty(cid(), vec![100, 200], 50, 1000),
+        ),
+        (
+            call(escrow::ContributeCall::<Runtime> { id: 1, amount: 50 }),
+            EscrowCall::contribute(1, 50),
+        ),
+        (
+            call(escrow::SubmitCall::<Runtime> {
+                id: 1,
+                milestone: 0,
+                submission: cid(),
+            }),
+            EscrowCall::submit(1, 0, cid()),
+        ),
+        (
+            call(escrow::ApproveCall::<Runtime> {
+                id: 1,
+                submission_id: 2,
+            }),
+            EscrowCall::approve(1, 2),
+        ),
+        (
+            call(escrow::RejectCall::<Runtime> {
+                id: 1,
+                submission_id: 2,
+            }),
+            EscrowCall::reject(1, 2),
+        ),
+        (
+            call(escrow::OpenDisputeCall::<Runtime> {
+                id: 1,
+                submission_id: 2,
+                evidence: cid(),
+            }),
+            EscrowCall::open_dispute(1, 2, cid()),
+        ),
+        (
+            call(escrow::VoteDisputeCall::<Runtime> {
+                id: 1,
+                submission_id: 2,
+                approve: true,
+            }),
+            EscrowCall::vote_dispute(1, 2, true),
+        ),
+        (
+            call(escrow::SetApproversCall::<Runtime> {
+                id: 1,
+                uids: vec![3, 4],
+                threshold: 2,
+            }),
+            EscrowCall::set_approvers(1, vec![3, 4], 2),
+        ),
+        (
+            call(escrow::SetVestingCall::<Runtime> { id: 1, period: 100 }),
+            EscrowCall::set_vesting(1, 100),
+        ),
+        (
+            call(escrow::VestCall::<Runtime> {
+                _runtime: PhantomData,
+            }),
+            EscrowCall::vest(),
+        ),
+    ];
+    for (call, expected) in calls {
+        assert_eq!(call, runtime::Call::Escrow(expected));
+    }
+}
+
+#[test]
+fn escrow_events() {
+    assert_eq!(
+        event::<escrow::EscrowPostedEvent<Runtime>>(EscrowEvent::EscrowPosted(
+            1,
+            alice(),
+            50,
+            cid()
+        )),
+        escrow::EscrowPostedEvent {
+            id: 1,
+            poster: alice(),
+            deposit: 50,
+            description: cid(),
+        }
+    );
+    assert_eq!(
+        event::<escrow::EscrowContributionEvent<Runtime>>(EscrowEvent::EscrowContribution(
+            1,
+            alice(),
+            50,
+            150
+        )),
+        escrow::EscrowContributionEvent {
+            id: 1,
+            contributor: alice(),
+            amount: 50,
+            total: 150,
+        }
+    );
+    assert_eq!(
+        event::<escrow::MilestoneSubmittedEvent<Runtime>>(EscrowEvent::MilestoneSubmitted(
+            1,
+            2,
+            0,
+            alice(),
+            cid()
+        )),
+        escrow::MilestoneSubmittedEvent {
+            id: 1,
+            submission_id: 2,
+            milestone: 0,
+            submitter: alice(),
+            submission: cid(),
+        }
+    );
+    assert_eq!(
+        event::<escrow::MilestonePaidEvent<Runtime>>(EscrowEvent::MilestonePaid(
+            1,
+            2,
+            0,
+            alice(),
+            100
+        )),
+        escrow::MilestonePaidEvent {
+            id: 1,
+            submission_id: 2,
+            milestone: 0,
+            submitter: alice(),
+            amount: 100,
+        }
+    );
+    assert_eq!(
+        event::<escrow::EscrowCompletedEvent<Runtime>>(EscrowEvent::EscrowCompleted(1)),
+        escrow::EscrowCompletedEvent { id: 1 }
+    );
+    assert_eq!(
+        event::<escrow::EscrowRefundedEvent<Runtime>>(EscrowEvent::EscrowRefunded(1, alice(), 50)),
+        escrow::EscrowRefundedEvent {
+            id: 1,
+            contributor: alice(),
+            amount: 50,
+        }
+    );
+    assert_eq!(
+        event::<escrow::EscrowExpiredEvent<Runtime>>(EscrowEvent::EscrowExpired(1)),
+        escrow::EscrowExpiredEvent { id: 1 }
+    );
+    assert_eq!(
+        event::<escrow::MilestoneRejectedEvent<Runtime>>(EscrowEvent::MilestoneRejected(1, 2)),
+        escrow::MilestoneRejectedEvent {
+            id: 1,
+            submission_id: 2,
+        }
+    );
+    assert_eq!(
+        event::<escrow::DisputeOpenedEvent<Runtime>>(EscrowEvent::DisputeOpened(
+            1,
+            2,
+            alice(),
+            10,
+            cid()
+        )),
+        escrow::DisputeOpenedEvent {
+            id: 1,
+            submission_id: 2,
+            submitter: alice(),
+            bond: 10,
+            evidence: cid(),
+        }
+    );
+    assert_eq!(
+        event::<escrow::DisputeVotedEvent<Runtime>>(EscrowEvent::DisputeVoted(1, 2, bob(), true)),
+        escrow::DisputeVotedEvent {
+            id: 1,
+            submission_id: 2,
+            arbiter: bob(),
+            approve: true,
+        }
+    );
+    assert_eq!(
+        event::<escrow::DisputeResolvedEvent<Runtime>>(EscrowEvent::DisputeResolved(1, 2, false)),
+        escrow::DisputeResolvedEvent {
+            id: 1,
+            submission_id: 2,
+            approved: false,
+        }
+    );
+    assert_eq!(
+        event::<escrow::ApproversSetEvent<Runtime>>(EscrowEvent::ApproversSet(1, 2, vec![3, 4])),
+        escrow::ApproversSetEvent {
+            id: 1,
+            threshold: 2,
+            uids: vec![3, 4],
+        }
+    );
+    assert_eq!(
+        event::<escrow::MilestoneApprovedEvent<Runtime>>(EscrowEvent::MilestoneApproved(
+            1, 2, 3, 1
+        )),
+        escrow::MilestoneApprovedEvent {
+            id: 1,
+            submission_id: 2,
+            uid: 3,
+            approvals: 1,
+        }
+    );
+    assert_eq!(
+        event::<escrow::VestingSetEvent<Runtime>>(EscrowEvent::VestingSet(1, 100)),
+        escrow::VestingSetEvent { id: 1, period: 100 }
+    );
+    assert_eq!(
+        event::<escrow::VestingStartedEvent<Runtime>>(EscrowEvent::VestingStarted(
+            alice(),
+            100,
+            1000
+        )),
+        escrow::VestingStartedEvent {
+            account: alice(),
+            amount: 100,
+            period: 1000,
+        }
+    );
+    assert_eq!(
+        event::<escrow::VestedEvent<Runtime>>(EscrowEvent::Vested(alice(), 50)),
+        escrow::VestedEvent {
+            account: alice(),
+            locked: 50,
+        }
+    );
+    assert_eq!(
+        event::<escrow::DisputeDismissedEvent<Runtime>>(EscrowEvent::DisputeDismissed(1, 2)),
+        escrow::DisputeDismissedEvent {
+            id: 1,
+            submission_id: 2,
+        }
+    );
+}
+
+#[test]
+fn faucet_types() {
+    assert_eq!(
+        key(faucet::NextMintStore::<Runtime> { account: &alice() }),
+        faucet_pallet::NextMint::<runtime::Runtime>::hashed_key_for(alice())
+    );
+    assert_eq!(
+        key(faucet::PeriodStartStore::<Runtime> {
+            _runtime: PhantomData,
+        }),
+        faucet_pallet::PeriodStart::<runtime::Runtime>::hashed_key().to_vec()
+    );
+    assert_eq!(
+        key(faucet::PeriodMintsStore::<Runtime> {
+            _runtime: PhantomData,
+        }),
+        faucet_pallet::PeriodMints::<runtime::Runtime>::hashed_key().to_vec()
+    );
+    assert_eq!(
+        call(faucet::MintCall::<Runtime> {
+            account: &alice(),
+            nonce: 7,
+        }),
+        runtime::Call::Faucet(faucet_pallet::Call::mint(alice(), 7))
+    );
+    assert_eq!(
+        event::<faucet::MintedEvent<Runtime>>(faucet_pallet::Event::<runtime::Runtime>::Minted(
+            alice(),
+            100
+        )),
+        faucet::MintedEvent {
+            account: alice(),
+            amount: 100,
+        }
+    );
+}
+
+#[test]
+fn indices_types() {
+    assert_eq!(
+        key(indices::AccountsStore::<Runtime> { index: 42 }),
+        pallet_indices::Accounts::<runtime::Runtime>::hashed_key_for(42)
+    );
+    assert_eq!(
+        call(indices::ClaimCall::<Runtime> { index: 42 }),
+        runtime::Call::Indices(pallet_indices::Call::claim(42))
+    );
+    assert_eq!(
+        call(indices::FreeCall::<Runtime> { index: 42 }),
+        runtime::Call::Indices(pallet_indices::Call::free(42))
+    );
+    assert_eq!(
+        event::<indices::IndexAssignedEvent<Runtime>>(
+            pallet_indices::Event::<runtime::Runtime>::IndexAssigned(alice(), 42)
+        ),
+        indices::IndexAssignedEvent {
+            account: alice(),
+            index: 42,
+        }
+    );
+    assert_eq!(
+        event::<indices::IndexFreedEvent<Runtime>>(
+            pallet_indices::Event::<runtime::Runtime>::IndexFreed(42)
+        ),
+        indices::IndexFreedEvent { index: 42 }
+    );
+}
+
+#[test]
+fn proxy_types() {
+    assert_eq!(
+        call(proxy::AddProxyCall::<Runtime> {
+            delegate: &bob(),
+            proxy_type: ProxyType::BountyApprover,
+            delay: 0,
+        }),
+        runtime::Call::Proxy(pallet_proxy::Call::add_proxy(
+            bob(),
+            ProxyType::BountyApprover,
+            0
+        ))
+    );
+    assert_eq!(
+        call(proxy::RemoveProxyCall::<Runtime> {
+            delegate: &bob(),
+            proxy_type: ProxyType::BountyApprover,
+            delay: 0,
+        }),
+        runtime::Call::Proxy(pallet_proxy::Call::remove_proxy(
+            bob(),
+            ProxyType::BountyApprover,
+            0
+        ))
+    );
+    let approve = escrow::ApproveCall::<Runtime> {
+        id: 1,
+        submission_id: 2,
+    };
+    assert_eq!(
+        call(proxy::ProxyCall::<Runtime> {
+            real: &alice(),
+            force_proxy_type: None,
+            call: &encode(approve.clone()),
+        }),
+        runtime::Call::Proxy(pallet_proxy::Call::proxy(
+            alice(),
+            None,
+            Box::new(call(approve))
+        ))
+    );
+    assert_eq!(
+        event::<proxy::ProxyExecutedEvent<Runtime>>(
+            pallet_proxy::Event::<runtime::Runtime>::ProxyExecuted(Ok(()))
+        ),
+        proxy::ProxyExecutedEvent {
+            result: Ok(()),
+            _runtime: PhantomData,
+        }
+    );
+}
+
+#[test]
+fn proxy_storage() {
+    let storage = pallet_balances::GenesisConfig::<runtime::Runtime> {
+        balances: vec![(alice(), 1 << 60)],
+    }
+    .build_storage()
+    .unwrap();
+    sp_io::TestExternalities::new(storage).execute_with(|| {
+        pallet_proxy::Call::<runtime::Runtime>::add_proxy(bob(), ProxyType::BountyApprover, 0)
+            .dispatch_bypass_filter(runtime::Origin::signed(alice()))
+            .unwrap();
+        let key = key(proxy::ProxiesStore::<Runtime> { account: &alice() });
+        assert_eq!(
+            key,
+            pallet_proxy::Proxies::<runtime::Runtime>::hashed_key_for(alice())
+        );
+        let (proxies, deposit) =
+            <(Vec<proxy::ProxyDefinitionOf<Runtime>>, BalanceOf<Runtime>)>::decode(
+                &mut &unhashed::get_raw(&key).unwrap()[..],
+            )
+            .unwrap();
+        assert_eq!(
+            proxies,
+            vec![ProxyDefinition {
+                delegate: bob(),
+                proxy_type: ProxyType::BountyApprover,
+                delay: 0,
+            }]
+        );
+        assert_eq!(
+            deposit,
+            pallet_balances::Module::<runtime::Runtime>::reserved_balance(alice())
+        );
+    });
+}
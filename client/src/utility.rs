@@ -0,0 +1,50 @@
+//! Client for batching calls.
+use crate::Runtime;
+use core::marker::PhantomData;
+use parity_scale_codec::{Decode, Encode};
+use substrate_subxt::sp_runtime::DispatchError;
+use substrate_subxt::system::{System, SystemEventsDecoder};
+use substrate_subxt::{module, Call, Encoded, Event, ExtrinsicSuccess};
+use sunshine_client_utils::Result;
+
+#[module]
+pub trait Utility: System {}
+
+// ------------------------------------------------------------------------------
+// Calls
+// ------------------------------------------------------------------------------
+
+/// Dispatches `calls` in order, reverting all of them if one fails.
+#[derive(Clone, Debug, Eq, PartialEq, Call, Encode)]
+pub struct BatchAllCall<'a, T: Utility> {
+    pub calls: &'a [Encoded],
+    pub _runtime: PhantomData<T>,
+}
+
+// ------------------------------------------------------------------------------
+// Events
+// ------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct BatchInterruptedEvent<T: Utility> {
+    pub index: u32,
+    pub error: DispatchError,
+    pub _runtime: PhantomData<T>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Event, Decode)]
+pub struct BatchCompletedEvent<T: Utility> {
+    pub _runtime: PhantomData<T>,
+}
+
+/// Decodes every `E` emitted by an extrinsic, like the events of the calls in
+/// a batch.
+pub(crate) fn find_events<E: Event<Runtime>>(result: &ExtrinsicSuccess<Runtime>) -> Result<Vec<E>> {
+    let mut events = Vec::new();
+    for raw in &result.events {
+        if raw.module == E::MODULE && raw.variant == E::EVENT {
+            events.push(E::decode(&mut &raw.data[..])?);
+        }
+    }
+    Ok(events)
+}
@@ -0,0 +1,676 @@
+//! Sqlite database of the indexed events.
+//!
+//! Balances are stored as decimal strings, because they don't fit into the
+//! integers of sqlite. Accounts are stored in their ss58 encoding.
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Kind of bounties posted with the bounty pallet.
+pub const BOUNTY: &str = "bounty";
+/// Kind of bounties posted with the escrow pallet.
+pub const ESCROW: &str = "escrow";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS progress (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    block_number INTEGER NOT NULL,
+    block_hash TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    block_number INTEGER NOT NULL,
+    extrinsic_index INTEGER,
+    module TEXT NOT NULL,
+    name TEXT NOT NULL,
+    details TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bounties (
+    kind TEXT NOT NULL,
+    id INTEGER NOT NULL,
+    poster TEXT,
+    description TEXT NOT NULL,
+    repo TEXT,
+    block_number INTEGER NOT NULL,
+    PRIMARY KEY (kind, id)
+);
+CREATE TABLE IF NOT EXISTS pending_repos (
+    kind TEXT NOT NULL,
+    id INTEGER NOT NULL,
+    PRIMARY KEY (kind, id)
+);
+CREATE TABLE IF NOT EXISTS contributions (
+    kind TEXT NOT NULL,
+    bounty_id INTEGER NOT NULL,
+    contributor TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block_number INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS payouts (
+    kind TEXT NOT NULL,
+    bounty_id INTEGER NOT NULL,
+    submission_id INTEGER NOT NULL,
+    recipient TEXT,
+    amount TEXT NOT NULL,
+    block_number INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transfers (
+    sender TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block_number INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_block ON events (block_number);
+CREATE INDEX IF NOT EXISTS bounties_repo ON bounties (repo);
+CREATE INDEX IF NOT EXISTS contributions_contributor ON contributions (contributor);
+CREATE INDEX IF NOT EXISTS payouts_bounty ON payouts (kind, bounty_id);
+CREATE INDEX IF NOT EXISTS payouts_recipient ON payouts (recipient);
+CREATE INDEX IF NOT EXISTS transfers_sender ON transfers (sender);
+CREATE INDEX IF NOT EXISTS transfers_recipient ON transfers (recipient);
+";
+
+/// Last indexed block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Progress {
+    pub block_number: u32,
+    pub block_hash: String,
+}
+
+/// An event of any module.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Event {
+    pub block_number: u32,
+    pub extrinsic_index: Option<u32>,
+    pub module: String,
+    pub name: String,
+    /// Debug representation of the decoded event.
+    pub details: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Bounty {
+    pub kind: &'static str,
+    pub id: i64,
+    /// Not emitted by the bounty pallet.
+    pub poster: Option<String>,
+    /// Cid of the bounty body.
+    pub description: String,
+    /// Repository of the bounty body, see `BountyBody::repo`. Bounties are
+    /// indexed without it and their body is fetched in the background.
+    pub repo: Option<String>,
+    pub block_number: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Contribution {
+    pub kind: &'static str,
+    pub bounty_id: i64,
+    pub contributor: String,
+    pub amount: String,
+    pub block_number: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Payout {
+    pub kind: &'static str,
+    pub bounty_id: i64,
+    pub submission_id: i64,
+    /// Not emitted by the bounty pallet.
+    pub recipient: Option<String>,
+    pub amount: String,
+    pub block_number: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Transfer {
+    pub sender: String,
+    pub recipient: String,
+    pub amount: String,
+    pub block_number: u32,
+}
+
+/// Rows derived from the events of a block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlockRows {
+    pub events: Vec<Event>,
+    pub bounties: Vec<Bounty>,
+    pub contributions: Vec<Contribution>,
+    pub payouts: Vec<Payout>,
+    pub transfers: Vec<Transfer>,
+}
+
+fn kind(row: &Row, index: usize) -> rusqlite::Result<&'static str> {
+    match row.get::<_, String>(index)?.as_str() {
+        BOUNTY => Ok(BOUNTY),
+        _ => Ok(ESCROW),
+    }
+}
+
+fn bounty(row: &Row) -> rusqlite::Result<Bounty> {
+    Ok(Bounty {
+        kind: kind(row, 0)?,
+        id: row.get(1)?,
+        poster: row.get(2)?,
+        description: row.get(3)?,
+        repo: row.get(4)?,
+        block_number: row.get(5)?,
+    })
+}
+
+fn contribution(row: &Row) -> rusqlite::Result<Contribution> {
+    Ok(Contribution {
+        kind: kind(row, 0)?,
+        bounty_id: row.get(1)?,
+        contributor: row.get(2)?,
+        amount: row.get(3)?,
+        block_number: row.get(4)?,
+    })
+}
+
+fn payout(row: &Row) -> rusqlite::Result<Payout> {
+    Ok(Payout {
+        kind: kind(row, 0)?,
+        bounty_id: row.get(1)?,
+        submission_id: row.get(2)?,
+        recipient: row.get(3)?,
+        amount: row.get(4)?,
+        block_number: row.get(5)?,
+    })
+}
+
+fn transfer(row: &Row) -> rusqlite::Result<Transfer> {
+    Ok(Transfer {
+        sender: row.get(0)?,
+        recipient: row.get(1)?,
+        amount: row.get(2)?,
+        block_number: row.get(3)?,
+    })
+}
+
+pub struct Database {
+    conn: Mutex<Connection>,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    fn new(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Returns the last indexed block.
+    pub fn progress(&self) -> rusqlite::Result<Option<Progress>> {
+        self.conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT block_number, block_hash FROM progress WHERE id = 0",
+                params![],
+                |row| {
+                    Ok(Progress {
+                        block_number: row.get(0)?,
+                        block_hash: row.get(1)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    /// Returns the block to resume indexing at, the block after the last
+    /// indexed block.
+    pub fn next_block(&self) -> rusqlite::Result<u32> {
+        Ok(match self.progress()? {
+            Some(progress) => progress.block_number + 1,
+            None => 0,
+        })
+    }
+
+    /// Inserts the rows of a block and marks it as indexed in one
+    /// transaction, so a restart resumes after the last complete block.
+    pub fn insert_block(&self, progress: &Progress, rows: &BlockRows) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for event in &rows.events {
+            tx.execute(
+                "INSERT INTO events (block_number, extrinsic_index, module, name, details)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    event.block_number,
+                    event.extrinsic_index,
+                    event.module,
+                    event.name,
+                    event.details
+                ],
+            )?;
+        }
+        for bounty in &rows.bounties {
+            tx.execute(
+                "INSERT OR REPLACE INTO bounties
+                 (kind, id, poster, description, repo, block_number)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    bounty.kind,
+                    bounty.id,
+                    bounty.poster,
+                    bounty.description,
+                    bounty.repo,
+                    bounty.block_number
+                ],
+            )?;
+            if bounty.repo.is_none() {
+                tx.execute(
+                    "INSERT OR IGNORE INTO pending_repos (kind, id) VALUES (?1, ?2)",
+                    params![bounty.kind, bounty.id],
+                )?;
+            }
+        }
+        for contribution in &rows.contributions {
+            tx.execute(
+                "INSERT INTO contributions (kind, bounty_id, contributor, amount, block_number)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    contribution.kind,
+                    contribution.bounty_id,
+                    contribution.contributor,
+                    contribution.amount,
+                    contribution.block_number
+                ],
+            )?;
+        }
+        for payout in &rows.payouts {
+            tx.execute(
+                "INSERT INTO payouts
+                 (kind, bounty_id, submission_id, recipient, amount, block_number)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    payout.kind,
+                    payout.bounty_id,
+                    payout.submission_id,
+                    payout.recipient,
+                    payout.amount,
+                    payout.block_number
+                ],
+            )?;
+        }
+        for transfer in &rows.transfers {
+            tx.execute(
+                "INSERT INTO transfers (sender, recipient, amount, block_number)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    transfer.sender,
+                    transfer.recipient,
+                    transfer.amount,
+                    transfer.block_number
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO progress (id, block_number, block_hash) VALUES (0, ?1, ?2)",
+            params![progress.block_number, progress.block_hash],
+        )?;
+        tx.commit()
+    }
+
+    /// Returns the events emitted in block `block_number`.
+    pub fn events(&self, block_number: u32) -> rusqlite::Result<Vec<Event>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT block_number, extrinsic_index, module, name, details
+             FROM events WHERE block_number = ?1",
+        )?;
+        let rows = stmt.query_map(params![block_number], |row| {
+            Ok(Event {
+                block_number: row.get(0)?,
+                extrinsic_index: row.get(1)?,
+                module: row.get(2)?,
+                name: row.get(3)?,
+                details: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn bounty(&self, kind: &str, id: i64) -> rusqlite::Result<Option<Bounty>> {
+        self.conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT kind, id, poster, description, repo, block_number
+                 FROM bounties WHERE kind = ?1 AND id = ?2",
+                params![kind, id],
+                bounty,
+            )
+            .optional()
+    }
+
+    /// Returns the bounties whose body wasn't fetched yet.
+    pub fn pending_repos(&self) -> rusqlite::Result<Vec<Bounty>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT b.kind, b.id, b.poster, b.description, b.repo, b.block_number
+             FROM bounties b JOIN pending_repos p ON p.kind = b.kind AND p.id = b.id
+             ORDER BY b.block_number",
+        )?;
+        let rows = stmt.query_map(params![], bounty)?;
+        rows.collect()
+    }
+
+    /// Sets the repository of a bounty after its body was fetched. `None`
+    /// if the body has no repository.
+    pub fn set_repo(&self, kind: &str, id: i64, repo: Option<&str>) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE bounties SET repo = ?3 WHERE kind = ?1 AND id = ?2",
+            params![kind, id, repo],
+        )?;
+        tx.execute(
+            "DELETE FROM pending_repos WHERE kind = ?1 AND id = ?2",
+            params![kind, id],
+        )?;
+        tx.commit()
+    }
+
+    /// Returns the bounties `account` contributed to.
+    pub fn contributed_bounties(&self, account: &str) -> rusqlite::Result<Vec<Bounty>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT b.kind, b.id, b.poster, b.description, b.repo, b.block_number
+             FROM bounties b JOIN contributions c ON c.kind = b.kind AND c.bounty_id = b.id
+             WHERE c.contributor = ?1 ORDER BY b.block_number",
+        )?;
+        let rows = stmt.query_map(params![account], bounty)?;
+        rows.collect()
+    }
+
+    /// Returns the contributions of `account`.
+    pub fn contributions(&self, account: &str) -> rusqlite::Result<Vec<Contribution>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT kind, bounty_id, contributor, amount, block_number
+             FROM contributions WHERE contributor = ?1 ORDER BY block_number",
+        )?;
+        let rows = stmt.query_map(params![account], contribution)?;
+        rows.collect()
+    }
+
+    /// Returns the payouts of a bounty.
+    pub fn bounty_payouts(&self, kind: &str, id: i64) -> rusqlite::Result<Vec<Payout>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT kind, bounty_id, submission_id, recipient, amount, block_number
+             FROM payouts WHERE kind = ?1 AND bounty_id = ?2 ORDER BY block_number",
+        )?;
+        let rows = stmt.query_map(params![kind, id], payout)?;
+        rows.collect()
+    }
+
+    /// Returns the payouts to `account`.
+    pub fn account_payouts(&self, account: &str) -> rusqlite::Result<Vec<Payout>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT kind, bounty_id, submission_id, recipient, amount, block_number
+             FROM payouts WHERE recipient = ?1 ORDER BY block_number",
+        )?;
+        let rows = stmt.query_map(params![account], payout)?;
+        rows.collect()
+    }
+
+    /// Returns the payouts of the bounties of `repo`, like
+    /// `github.com/<owner>/<repo>`.
+    pub fn repo_payouts(&self, repo: &str) -> rusqlite::Result<Vec<Payout>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT p.kind, p.bounty_id, p.submission_id, p.recipient, p.amount, p.block_number
+             FROM payouts p JOIN bounties b ON b.kind = p.kind AND b.id = p.bounty_id
+             WHERE b.repo = ?1 ORDER BY p.block_number",
+        )?;
+        let rows = stmt.query_map(params![repo], payout)?;
+        rows.collect()
+    }
+
+    /// Returns the transfers from and to `account`.
+    pub fn transfers(&self, account: &str) -> rusqlite::Result<Vec<Transfer>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT sender, recipient, amount, block_number
+             FROM transfers WHERE sender = ?1 OR recipient = ?1 ORDER BY block_number",
+        )?;
+        let rows = stmt.query_map(params![account], transfer)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db() -> Database {
+        Database::new(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn progress(block_number: u32) -> Progress {
+        Progress {
+            block_number,
+            block_hash: format!("0x{:02x}", block_number),
+        }
+    }
+
+    fn escrow(id: i64, block_number: u32) -> Bounty {
+        Bounty {
+            kind: ESCROW,
+            id,
+            poster: Some("poster".into()),
+            description: format!("cid{}", id),
+            repo: None,
+            block_number,
+        }
+    }
+
+    fn contribution(id: i64, contributor: &str, block_number: u32) -> Contribution {
+        Contribution {
+            kind: ESCROW,
+            bounty_id: id,
+            contributor: contributor.into(),
+            amount: "100".into(),
+            block_number,
+        }
+    }
+
+    fn payout(kind: &'static str, id: i64, recipient: Option<&str>, block_number: u32) -> Payout {
+        Payout {
+            kind,
+            bounty_id: id,
+            submission_id: 0,
+            recipient: recipient.map(Into::into),
+            amount: "50".into(),
+            block_number,
+        }
+    }
+
+    #[test]
+    fn resumes_after_last_block() {
+        let db = db();
+        assert_eq!(db.progress().unwrap(), None);
+        assert_eq!(db.next_block().unwrap(), 0);
+
+        db.insert_block(&progress(0), &BlockRows::default())
+            .unwrap();
+        db.insert_block(&progress(7), &BlockRows::default())
+            .unwrap();
+        assert_eq!(db.progress().unwrap(), Some(progress(7)));
+        assert_eq!(db.next_block().unwrap(), 8);
+    }
+
+    #[test]
+    fn failed_block_is_not_marked_indexed() {
+        let db = db();
+        db.insert_block(&progress(1), &BlockRows::default())
+            .unwrap();
+        let mut rows = BlockRows::default();
+        rows.events.push(Event {
+            block_number: 2,
+            extrinsic_index: Some(1),
+            module: "Escrow".into(),
+            name: "EscrowPosted".into(),
+            details: "EscrowPosted(..)".into(),
+        });
+        rows.bounties.push(escrow(1, 2));
+        rows.contributions.push(contribution(1, "alice", 2));
+        // Fails the block after its events and bounties were inserted.
+        db.conn
+            .lock()
+            .unwrap()
+            .execute_batch("DROP TABLE contributions")
+            .unwrap();
+
+        assert!(db.insert_block(&progress(2), &rows).is_err());
+        assert_eq!(db.next_block().unwrap(), 2);
+        assert!(db.events(2).unwrap().is_empty());
+        assert_eq!(db.bounty(ESCROW, 1).unwrap(), None);
+    }
+
+    #[test]
+    fn queries_bounty_history() {
+        let db = db();
+        let mut rows = BlockRows::default();
+        rows.events.push(Event {
+            block_number: 1,
+            extrinsic_index: Some(1),
+            module: "Escrow".into(),
+            name: "EscrowPosted".into(),
+            details: "EscrowPosted(..)".into(),
+        });
+        rows.bounties.push(escrow(1, 1));
+        rows.bounties.push(escrow(2, 1));
+        rows.contributions.push(contribution(1, "alice", 1));
+        rows.contributions.push(contribution(2, "bob", 1));
+        db.insert_block(&progress(1), &rows).unwrap();
+        db.set_repo(ESCROW, 1, Some("github.com/owner/repo"))
+            .unwrap();
+
+        let mut rows = BlockRows::default();
+        rows.contributions.push(contribution(1, "alice", 2));
+        rows.payouts.push(payout(ESCROW, 1, Some("carol"), 2));
+        rows.payouts.push(payout(ESCROW, 2, Some("carol"), 2));
+        rows.payouts.push(payout(BOUNTY, 1, None, 2));
+        rows.transfers.push(Transfer {
+            sender: "alice".into(),
+            recipient: "bob".into(),
+            amount: "10".into(),
+            block_number: 2,
+        });
+        db.insert_block(&progress(2), &rows).unwrap();
+
+        assert_eq!(db.events(1).unwrap().len(), 1);
+        assert!(db.events(2).unwrap().is_empty());
+        assert_eq!(
+            db.bounty(ESCROW, 1).unwrap().unwrap().repo.as_deref(),
+            Some("github.com/owner/repo")
+        );
+        assert_eq!(db.bounty(BOUNTY, 1).unwrap(), None);
+
+        let bounties = db.contributed_bounties("alice").unwrap();
+        assert_eq!(bounties.len(), 1);
+        assert_eq!(bounties[0].id, 1);
+        assert_eq!(db.contributions("alice").unwrap().len(), 2);
+
+        assert_eq!(
+            db.bounty_payouts(ESCROW, 1).unwrap(),
+            vec![payout(ESCROW, 1, Some("carol"), 2)]
+        );
+        assert_eq!(
+            db.bounty_payouts(BOUNTY, 1).unwrap(),
+            vec![payout(BOUNTY, 1, None, 2)]
+        );
+        assert_eq!(db.account_payouts("carol").unwrap().len(), 2);
+        assert_eq!(
+            db.repo_payouts("github.com/owner/repo").unwrap(),
+            vec![payout(ESCROW, 1, Some("carol"), 2)]
+        );
+
+        assert_eq!(db.transfers("alice").unwrap().len(), 1);
+        assert_eq!(db.transfers("bob").unwrap().len(), 1);
+        assert!(db.transfers("carol").unwrap().is_empty());
+    }
+
+    /// Sums the amounts of the contributions of `account`.
+    fn contributed(db: &Database, account: &str) -> u128 {
+        db.contributions(account)
+            .unwrap()
+            .iter()
+            .map(|contribution| contribution.amount.parse::<u128>().unwrap())
+            .sum()
+    }
+
+    #[test]
+    fn contributions_include_deposits_and_legacy_bounties() {
+        let db = db();
+        let mut rows = BlockRows::default();
+        rows.bounties.push(escrow(1, 1));
+        // The deposit of the poster.
+        rows.contributions.push(contribution(1, "poster", 1));
+        rows.bounties.push(Bounty {
+            kind: BOUNTY,
+            id: 1,
+            poster: None,
+            description: "cid".into(),
+            repo: None,
+            block_number: 1,
+        });
+        db.insert_block(&progress(1), &rows).unwrap();
+
+        let mut rows = BlockRows::default();
+        rows.contributions.push(contribution(1, "alice", 2));
+        rows.contributions.push(Contribution {
+            kind: BOUNTY,
+            ..contribution(1, "alice", 2)
+        });
+        db.insert_block(&progress(2), &rows).unwrap();
+
+        assert_eq!(contributed(&db, "poster"), 100);
+        assert_eq!(contributed(&db, "alice"), 200);
+        assert_eq!(
+            db.contributed_bounties("poster").unwrap(),
+            vec![escrow(1, 1)]
+        );
+        let kinds = db
+            .contributed_bounties("alice")
+            .unwrap()
+            .iter()
+            .map(|bounty| (bounty.kind, bounty.id))
+            .collect::<Vec<_>>();
+        assert_eq!(kinds.len(), 2);
+        assert!(kinds.contains(&(ESCROW, 1)));
+        assert!(kinds.contains(&(BOUNTY, 1)));
+    }
+
+    #[test]
+    fn pending_repos_are_backfilled() {
+        let db = db();
+        let mut rows = BlockRows::default();
+        rows.bounties.push(escrow(1, 1));
+        rows.bounties.push(escrow(2, 1));
+        db.insert_block(&progress(1), &rows).unwrap();
+        assert_eq!(
+            db.pending_repos().unwrap(),
+            vec![escrow(1, 1), escrow(2, 1)]
+        );
+
+        db.set_repo(ESCROW, 1, Some("github.com/owner/repo"))
+            .unwrap();
+        // A body without a repository isn't fetched again.
+        db.set_repo(ESCROW, 2, None).unwrap();
+        assert!(db.pending_repos().unwrap().is_empty());
+        assert_eq!(
+            db.bounty(ESCROW, 1).unwrap().unwrap().repo.as_deref(),
+            Some("github.com/owner/repo")
+        );
+        assert_eq!(db.bounty(ESCROW, 2).unwrap().unwrap().repo, None);
+    }
+}
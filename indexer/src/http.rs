@@ -0,0 +1,132 @@
+//! Read-only json api over the indexed data.
+//!
+//! | Route                              | Returns                                  |
+//! |------------------------------------|------------------------------------------|
+//! | `/status`                          | last indexed block                       |
+//! | `/blocks/:number/events`           | events of a block                        |
+//! | `/bounties/:kind/:id`              | a bounty, `kind` is `bounty` or `escrow` |
+//! | `/bounties/:kind/:id/payouts`      | payouts of a bounty                      |
+//! | `/accounts/:account/bounties`      | bounties the account contributed to      |
+//! | `/accounts/:account/contributions` | contributions of the account             |
+//! | `/accounts/:account/payouts`       | payouts to the account                   |
+//! | `/accounts/:account/transfers`     | transfers from and to the account        |
+//! | `/payouts?repo=<repo>`             | payouts of the bounties of a repository  |
+//! | `/github/:handle`                  | uid and devices that proved the handle   |
+//!
+//! Repositories include their host, like `github.com/<owner>/<repo>`.
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use sunshine_client::github::GithubClient;
+use sunshine_client::{Client, Uid};
+use tide::{Body, Request, StatusCode};
+
+#[derive(Clone)]
+struct State {
+    db: Arc<Database>,
+    client: Arc<Client>,
+}
+
+fn json<T: Serialize>(value: &T) -> tide::Result {
+    Ok(Body::from_json(value)?.into())
+}
+
+fn number<T: std::str::FromStr>(req: &Request<State>, param: &str) -> tide::Result<T> {
+    req.param(param)?
+        .parse()
+        .map_err(|_| tide::Error::from_str(StatusCode::BadRequest, "invalid number"))
+}
+
+async fn status(req: Request<State>) -> tide::Result {
+    json(&req.state().db.progress()?)
+}
+
+async fn block_events(req: Request<State>) -> tide::Result {
+    json(&req.state().db.events(number(&req, "number")?)?)
+}
+
+async fn bounty(req: Request<State>) -> tide::Result {
+    match req
+        .state()
+        .db
+        .bounty(req.param("kind")?, number(&req, "id")?)?
+    {
+        Some(bounty) => json(&bounty),
+        None => Ok(StatusCode::NotFound.into()),
+    }
+}
+
+async fn bounty_payouts(req: Request<State>) -> tide::Result {
+    json(
+        &req.state()
+            .db
+            .bounty_payouts(req.param("kind")?, number(&req, "id")?)?,
+    )
+}
+
+async fn contributed_bounties(req: Request<State>) -> tide::Result {
+    json(&req.state().db.contributed_bounties(req.param("account")?)?)
+}
+
+async fn contributions(req: Request<State>) -> tide::Result {
+    json(&req.state().db.contributions(req.param("account")?)?)
+}
+
+async fn account_payouts(req: Request<State>) -> tide::Result {
+    json(&req.state().db.account_payouts(req.param("account")?)?)
+}
+
+async fn transfers(req: Request<State>) -> tide::Result {
+    json(&req.state().db.transfers(req.param("account")?)?)
+}
+
+#[derive(Deserialize)]
+struct RepoQuery {
+    repo: String,
+}
+
+async fn repo_payouts(req: Request<State>) -> tide::Result {
+    let query: RepoQuery = req.query()?;
+    json(&req.state().db.repo_payouts(&query.repo)?)
+}
+
+#[derive(Serialize)]
+struct GithubIdentity {
+    uid: Uid,
+    devices: Vec<String>,
+}
+
+/// Resolves the github handle with the client, it isn't indexed.
+async fn github(req: Request<State>) -> tide::Result {
+    let client = &req.state().client;
+    let uid = client
+        .github_uid(req.param("handle")?)
+        .await
+        .map_err(|err| tide::Error::from_str(StatusCode::NotFound, err.to_string()))?;
+    let devices = client
+        .devices(uid)
+        .await
+        .map_err(|err| tide::Error::from_str(StatusCode::InternalServerError, err.to_string()))?;
+    json(&GithubIdentity {
+        uid,
+        devices: devices.iter().map(ToString::to_string).collect(),
+    })
+}
+
+/// Serves the json api on `addr`, like `127.0.0.1:8088`.
+pub async fn serve(db: Arc<Database>, client: Arc<Client>, addr: &str) -> std::io::Result<()> {
+    let mut app = tide::with_state(State { db, client });
+    app.at("/status").get(status);
+    app.at("/blocks/:number/events").get(block_events);
+    app.at("/bounties/:kind/:id").get(bounty);
+    app.at("/bounties/:kind/:id/payouts").get(bounty_payouts);
+    app.at("/accounts/:account/bounties")
+        .get(contributed_bounties);
+    app.at("/accounts/:account/contributions")
+        .get(contributions);
+    app.at("/accounts/:account/payouts").get(account_payouts);
+    app.at("/accounts/:account/transfers").get(transfers);
+    app.at("/payouts").get(repo_payouts);
+    app.at("/github/:handle").get(github);
+    app.listen(addr.to_string()).await
+}
@@ -0,0 +1,169 @@
+//! Indexes the bounty history of the sunshine chain into a sqlite database.
+//!
+//! The indexer follows the finalized blocks, so indexed events are never
+//! reverted, and resumes after the last indexed block when restarted. The
+//! repositories of bounties are fetched from their bodies in the background,
+//! so a slow offchain store doesn't hold up indexing.
+use async_std::future::timeout;
+use async_std::task;
+use db::{BlockRows, Bounty, Contribution, Database, Event, Payout, Progress, Transfer};
+use libipld::cache::Cache;
+use std::time::Duration;
+use sunshine_client::body::BountyBody;
+use sunshine_client::client::codec::Cid;
+use sunshine_client::client::{Client as _, Result};
+use sunshine_client::events::{BlockEvents, EventClient, SunshineEvent};
+use sunshine_client::Client;
+
+pub mod db;
+pub mod http;
+
+/// How long to wait for the body of a bounty before retrying it later.
+const BODY_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often bodies that weren't fetched yet are retried.
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Indexes the finalized blocks after the last indexed block until an error
+/// occurs.
+pub async fn index(client: &Client, db: &Database) -> Result<()> {
+    let mut blocks = client
+        .subscribe_sunshine_events_from(true, db.next_block()?)
+        .await?;
+    loop {
+        let block = blocks.next_block().await?;
+        let rows = block_rows(&block);
+        let progress = Progress {
+            block_number: block.block_number,
+            block_hash: format!("{:?}", block.block_hash),
+        };
+        db.insert_block(&progress, &rows)?;
+        log::debug!("indexed block {}", block.block_number);
+    }
+}
+
+/// Fetches the repositories of the bounties whose body wasn't fetched yet,
+/// retrying the bodies that failed every `BACKFILL_INTERVAL`.
+pub async fn backfill_repos(client: &Client, db: &Database) {
+    loop {
+        match db.pending_repos() {
+            Ok(bounties) => {
+                for bounty in bounties {
+                    let repo = match body(client, &bounty.description).await {
+                        Some(body) => body.repo(),
+                        None => continue,
+                    };
+                    if let Err(err) = db.set_repo(bounty.kind, bounty.id, repo.as_deref()) {
+                        log::error!("failed to set the repo of bounty {}: {:?}", bounty.id, err);
+                    }
+                }
+            }
+            Err(err) => log::error!("failed to query pending repos: {:?}", err),
+        }
+        task::sleep(BACKFILL_INTERVAL).await;
+    }
+}
+
+/// Fetches a bounty body from its `description`.
+async fn body(client: &Client, description: &str) -> Option<BountyBody> {
+    let cid: Cid = match description.parse() {
+        Ok(cid) => cid,
+        Err(err) => {
+            log::warn!("invalid bounty body cid {}: {:?}", description, err);
+            return None;
+        }
+    };
+    match timeout(BODY_TIMEOUT, client.offchain_client().get(&cid)).await {
+        Ok(Ok(body)) => Some(body),
+        Ok(Err(err)) => {
+            log::warn!("failed to fetch bounty body {}: {:?}", cid, err);
+            None
+        }
+        Err(_) => {
+            log::warn!("timed out fetching bounty body {}", cid);
+            None
+        }
+    }
+}
+
+fn block_rows(block: &BlockEvents) -> BlockRows {
+    let block_number = block.block_number;
+    let mut rows = BlockRows::default();
+    for record in &block.events {
+        let (module, name) = record.event.name();
+        rows.events.push(Event {
+            block_number,
+            extrinsic_index: record.extrinsic_index,
+            module: module.to_string(),
+            name: name.to_string(),
+            details: format!("{:?}", record.event),
+        });
+        match &record.event {
+            SunshineEvent::BountyPosted(event) => rows.bounties.push(Bounty {
+                kind: db::BOUNTY,
+                id: event.id as i64,
+                poster: None,
+                description: event.description.to_string(),
+                repo: None,
+                block_number,
+            }),
+            SunshineEvent::BountyRaiseContribution(event) => {
+                rows.contributions.push(Contribution {
+                    kind: db::BOUNTY,
+                    bounty_id: event.bounty_id as i64,
+                    contributor: event.contributor.to_string(),
+                    amount: event.amount.to_string(),
+                    block_number,
+                })
+            }
+            SunshineEvent::BountyPaymentExecuted(event) => rows.payouts.push(Payout {
+                kind: db::BOUNTY,
+                bounty_id: event.bounty_id as i64,
+                submission_id: event.submission_id as i64,
+                recipient: None,
+                amount: event.amount.to_string(),
+                block_number,
+            }),
+            SunshineEvent::EscrowPosted(event) => {
+                rows.bounties.push(Bounty {
+                    kind: db::ESCROW,
+                    id: event.id as i64,
+                    poster: Some(event.poster.to_string()),
+                    description: event.description.to_string(),
+                    repo: None,
+                    block_number,
+                });
+                // The deposit of the poster is its first contribution.
+                rows.contributions.push(Contribution {
+                    kind: db::ESCROW,
+                    bounty_id: event.id as i64,
+                    contributor: event.poster.to_string(),
+                    amount: event.deposit.to_string(),
+                    block_number,
+                });
+            }
+            SunshineEvent::EscrowContribution(event) => rows.contributions.push(Contribution {
+                kind: db::ESCROW,
+                bounty_id: event.id as i64,
+                contributor: event.contributor.to_string(),
+                amount: event.amount.to_string(),
+                block_number,
+            }),
+            SunshineEvent::MilestonePaid(event) => rows.payouts.push(Payout {
+                kind: db::ESCROW,
+                bounty_id: event.id as i64,
+                submission_id: event.submission_id as i64,
+                recipient: Some(event.submitter.to_string()),
+                amount: event.amount.to_string(),
+                block_number,
+            }),
+            SunshineEvent::Transfer(event) => rows.transfers.push(Transfer {
+                sender: event.from.to_string(),
+                recipient: event.to.to_string(),
+                amount: event.amount.to_string(),
+                block_number,
+            }),
+            _ => {}
+        }
+    }
+    rows
+}
@@ -0,0 +1,59 @@
+use async_std::task;
+use clap::Clap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sunshine_client::client::Result;
+use sunshine_client::Client;
+use sunshine_indexer::db::Database;
+use sunshine_indexer::{backfill_repos, http, index};
+
+/// How long to wait before resuming after the indexer failed.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Clap)]
+pub struct Opts {
+    #[clap(short = 'p', long = "path")]
+    pub path: Option<PathBuf>,
+    #[clap(short = 'c', long = "chain-spec")]
+    pub chain_spec: PathBuf,
+    /// Path of the sqlite database, `index.sqlite` in the config dir by default.
+    #[clap(long = "db")]
+    pub db: Option<PathBuf>,
+    /// Address of the json api.
+    #[clap(long = "http", default_value = "127.0.0.1:8088")]
+    pub http: String,
+}
+
+#[async_std::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    let opts = Opts::parse();
+    let root = opts
+        .path
+        .unwrap_or_else(|| dirs::config_dir().unwrap().join("sunshine-indexer"));
+    std::fs::create_dir_all(&root)?;
+    let db_path = opts.db.unwrap_or_else(|| root.join("index.sqlite"));
+    let db = Arc::new(Database::open(&db_path)?);
+    let client = Arc::new(Client::new(&root, &opts.chain_spec).await?);
+
+    let api = db.clone();
+    let api_client = client.clone();
+    let addr = opts.http;
+    task::spawn(async move {
+        if let Err(err) = http::serve(api, api_client, &addr).await {
+            log::error!("json api failed: {}", err);
+        }
+    });
+
+    let backfill_db = db.clone();
+    let backfill_client = client.clone();
+    task::spawn(async move { backfill_repos(&backfill_client, &backfill_db).await });
+
+    loop {
+        if let Err(err) = index(&client, &db).await {
+            log::error!("{:?}", err);
+            task::sleep(RETRY_DELAY).await;
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::Cli;
+use sc_cli::{CliConfiguration, Database, Runner, SharedParams};
+use sc_service::chain_ops::{export_blocks, import_blocks};
+use sc_service::{Configuration, DatabaseConfig, PartialComponents, TaskManager};
+use std::fs::{self, File};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Cache size used for rocksdb when none was configured.
+const DEFAULT_CACHE_SIZE: usize = 128;
+
+/// Applies the database backend selected with `--database`.
+///
+/// ParityDb is used by default and keeps using the `db` directory of
+/// existing deployments. Rocksdb and subdb live in separate `rocksdb` and
+/// `subdb` directories so that the backends can coexist while migrating.
+/// Databases that are not on disk are left untouched.
+pub fn select_database(runner: &mut Runner<Cli>, database: Option<Database>) {
+    let config = runner.config_mut();
+    let cache_size = match &config.database {
+        DatabaseConfig::RocksDb { cache_size, .. } => *cache_size,
+        _ => DEFAULT_CACHE_SIZE,
+    };
+    if let Some(dir) = database_dir(&config.database) {
+        config.database = database_config(&dir, database.unwrap_or(Database::ParityDb), cache_size);
+    }
+}
+
+fn database_dir(database: &DatabaseConfig) -> Option<PathBuf> {
+    database
+        .path()
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+}
+
+fn database_config(dir: &Path, database: Database, cache_size: usize) -> DatabaseConfig {
+    match database {
+        Database::ParityDb => DatabaseConfig::ParityDb {
+            path: dir.join("db"),
+        },
+        Database::RocksDb => DatabaseConfig::RocksDb {
+            path: dir.join("rocksdb"),
+            cache_size,
+        },
+        Database::SubDb => DatabaseConfig::SubDb {
+            path: dir.join("subdb"),
+        },
+    }
+}
+
+/// Removes the directory at the path when dropped, so temporary files are
+/// removed when a command fails or is interrupted.
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        match fs::remove_dir_all(&self.0) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                log::warn!("failed to remove {}: {}", self.0.display(), err)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Moves the database imported into the `staging` directory to `target` and
+/// removes the directory.
+fn commit_database(staging: TempDir, database: &Path, target: &Path) -> io::Result<()> {
+    fs::rename(database, target)?;
+    drop(staging);
+    Ok(())
+}
+
+/// The `migrate-db` command used to move the chain database to another backend.
+///
+/// Blocks are exported from the source database and imported into an empty
+/// database of the target backend, so no network sync is required. The
+/// exported blocks and the imported database are kept in a temporary
+/// `migrate-db` directory next to the databases. The database is only moved
+/// into place once the import finished, and the directory is removed when
+/// the migration finishes or fails.
+#[derive(Debug, StructOpt)]
+pub struct MigrateDbCmd {
+    /// Database backend to migrate from.
+    #[structopt(
+        long = "from",
+        value_name = "DB",
+        possible_values = &Database::variants(),
+        case_insensitive = true
+    )]
+    pub from: Database,
+
+    /// Database backend to migrate to.
+    #[structopt(
+        long = "to",
+        value_name = "DB",
+        possible_values = &Database::variants(),
+        case_insensitive = true
+    )]
+    pub to: Database,
+
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub shared_params: SharedParams,
+}
+
+impl MigrateDbCmd {
+    /// Run the migrate-db command.
+    pub fn run(
+        &self,
+        mut config: Configuration,
+    ) -> sc_cli::Result<(impl Future<Output = sc_cli::Result<()>>, TaskManager)> {
+        let dir = database_dir(&config.database)
+            .ok_or_else(|| sc_cli::Error::Input("database is not stored on disk".into()))?;
+        let source = database_config(&dir, self.from, DEFAULT_CACHE_SIZE);
+        let target = database_config(&dir, self.to, DEFAULT_CACHE_SIZE);
+        let target_path = target.path().unwrap().to_path_buf();
+        if source.path() == target.path() {
+            return Err(sc_cli::Error::Input(
+                "source and target backend must differ".into(),
+            ));
+        }
+        if target_path.exists() {
+            return Err(sc_cli::Error::Input(format!(
+                "{} already exists",
+                target_path.display()
+            )));
+        }
+
+        // Removes the leftovers of an interrupted migration.
+        let staging = TempDir(dir.join("migrate-db"));
+        if staging.0.exists() {
+            fs::remove_dir_all(&staging.0)?;
+        }
+        fs::create_dir_all(&staging.0)?;
+
+        // Both clients would register the same metrics otherwise.
+        config.prometheus_config = None;
+        config.database = source;
+        let PartialComponents {
+            client: source_client,
+            task_manager: source_task_manager,
+            ..
+        } = sunshine_node::new_partial(&config)?;
+        config.database = database_config(&staging.0, self.to, DEFAULT_CACHE_SIZE);
+        let staging_path = config.database.path().unwrap().to_path_buf();
+        let PartialComponents {
+            client,
+            import_queue,
+            task_manager,
+            ..
+        } = sunshine_node::new_partial(&config)?;
+
+        let to = self.to;
+        let task = async move {
+            let blocks = staging.0.join("blocks");
+            let output = File::create(&blocks)?;
+            export_blocks(source_client, output, 1, None, true).await?;
+            drop(source_task_manager);
+            let input = File::open(&blocks)?;
+            import_blocks(client, import_queue, input, false, true).await?;
+            commit_database(staging, &staging_path, &target_path)?;
+            log::info!(
+                "Migrated database to {}, start the node with `--database {:?}`",
+                target_path.display(),
+                to,
+            );
+            Ok(())
+        };
+        Ok((task, task_manager))
+    }
+}
+
+impl CliConfiguration for MigrateDbCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a staging directory with the exported blocks and a database.
+    fn staging(dir: &Path) -> (TempDir, PathBuf) {
+        let staging = TempDir(dir.join("migrate-db"));
+        let database = staging.0.join("rocksdb");
+        fs::create_dir_all(&database).unwrap();
+        fs::write(staging.0.join("blocks"), b"blocks").unwrap();
+        fs::write(database.join("CURRENT"), b"MANIFEST-000001").unwrap();
+        (staging, database)
+    }
+
+    #[test]
+    fn failed_migration_leaves_no_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let (staging, _) = staging(dir.path());
+        drop(staging);
+        assert!(!dir.path().join("migrate-db").exists());
+        assert!(!dir.path().join("rocksdb").exists());
+    }
+
+    #[test]
+    fn finished_migration_moves_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let (staging, database) = staging(dir.path());
+        let target = dir.path().join("rocksdb");
+        commit_database(staging, &database, &target).unwrap();
+        assert!(!dir.path().join("migrate-db").exists());
+        assert_eq!(
+            fs::read(target.join("CURRENT")).unwrap(),
+            b"MANIFEST-000001"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Development service that seals blocks on demand.
+//!
+//! Aura produces a block every `MILLISECS_PER_BLOCK`, which makes client and
+//! bot tests slow. The development service replaces aura and grandpa with
+//! manual sealing driven by the `engine_createBlock` and `engine_finalizeBlock`
+//! rpc calls. In instant mode a block is additionally sealed and finalized
+//! whenever a transaction enters the pool.
+use crate::rpc::extend_with_runtime_apis;
+use crate::Executor;
+use futures::stream::{self, StreamExt};
+use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
+use sc_consensus_manual_seal::{EngineCommand, ManualSealParams};
+use sc_network::NetworkService;
+use sc_service::{error::Error as ServiceError, Configuration, RpcHandlers, TaskManager};
+use sp_inherents::{InherentData, InherentDataProviders, InherentIdentifier, ProvideInherentData};
+use sp_runtime::traits::Block as BlockT;
+use sp_timestamp::{InherentError, INHERENT_IDENTIFIER};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sunshine_runtime::opaque::Block;
+use sunshine_runtime::{RuntimeApi, SLOT_DURATION};
+
+/// How the development service seals blocks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sealing {
+    /// Seal and finalize a block for every transaction that enters the pool.
+    Instant,
+    /// Seal blocks only when requested over rpc.
+    Manual,
+}
+
+impl FromStr for Sealing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "instant" => Ok(Self::Instant),
+            "manual" => Ok(Self::Manual),
+            _ => Err(format!("unknown sealing mode `{}`", s)),
+        }
+    }
+}
+
+/// Provides timestamps that advance by at least one slot per block.
+///
+/// The runtime accepts a single block per aura slot, so the timestamp is
+/// pushed into the future when blocks are sealed faster than `SLOT_DURATION`.
+#[derive(Default)]
+struct SlotTimestampProvider(Mutex<u64>);
+
+impl ProvideInherentData for SlotTimestampProvider {
+    fn inherent_identifier(&self) -> &'static InherentIdentifier {
+        &INHERENT_IDENTIFIER
+    }
+
+    fn provide_inherent_data(&self, data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| "Current time is before unix epoch")?
+            .as_millis() as u64;
+        let mut last = self.0.lock().unwrap();
+        *last = now.max(*last + SLOT_DURATION);
+        data.put_data(INHERENT_IDENTIFIER, &*last)
+    }
+
+    fn error_to_string(&self, error: &[u8]) -> Option<String> {
+        InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+    }
+}
+
+/// Builds a full node that seals blocks according to `sealing`.
+#[allow(clippy::type_complexity)]
+pub fn new_dev(
+    config: Configuration,
+    sealing: Sealing,
+) -> Result<
+    (
+        TaskManager,
+        RpcHandlers,
+        Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
+    ),
+    ServiceError,
+> {
+    let (client, backend, keystore, mut task_manager) =
+        sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
+    let client = Arc::new(client);
+    let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+    let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+        config.transaction_pool.clone(),
+        config.prometheus_registry(),
+        task_manager.spawn_handle(),
+        client.clone(),
+    );
+
+    let import_queue = sc_consensus_manual_seal::import_queue(
+        Box::new(client.clone()),
+        &task_manager.spawn_handle(),
+        config.prometheus_registry(),
+    );
+
+    let inherent_data_providers = InherentDataProviders::new();
+    inherent_data_providers
+        .register_provider(SlotTimestampProvider::default())
+        .map_err(|err| ServiceError::Other(format!("{:?}", err)))?;
+
+    let (network, network_status_sinks, system_rpc_tx, network_starter) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            on_demand: None,
+            block_announce_validator_builder: None,
+            finality_proof_request_builder: None,
+            finality_proof_provider: None,
+        })?;
+
+    if config.offchain_worker.enabled {
+        sc_service::build_offchain_workers(
+            &config,
+            backend.clone(),
+            task_manager.spawn_handle(),
+            client.clone(),
+            network.clone(),
+        );
+    }
+
+    let (command_sink, commands_stream) = futures::channel::mpsc::channel(1024);
+    let rpc_client = client.clone();
+    let rpc_extensions_builder = Box::new(move |_| {
+        let mut io = jsonrpc_core::IoHandler::default();
+        io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(
+            command_sink.clone(),
+        )));
+        extend_with_runtime_apis(&mut io, rpc_client.clone());
+        io
+    });
+
+    let prometheus_registry = config.prometheus_registry().cloned();
+
+    let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        network: network.clone(),
+        client: client.clone(),
+        keystore,
+        task_manager: &mut task_manager,
+        transaction_pool: transaction_pool.clone(),
+        telemetry_connection_sinks: Default::default(),
+        rpc_extensions_builder,
+        on_demand: None,
+        remote_blockchain: None,
+        backend,
+        network_status_sinks,
+        system_rpc_tx,
+        config,
+    })?;
+
+    let pool_commands = match sealing {
+        Sealing::Instant => transaction_pool
+            .pool()
+            .validated_pool()
+            .import_notification_stream()
+            .map(|_| EngineCommand::SealNewBlock {
+                create_empty: false,
+                finalize: true,
+                parent_hash: None,
+                sender: None,
+            })
+            .boxed(),
+        Sealing::Manual => stream::empty().boxed(),
+    };
+
+    let proposer = sc_basic_authorship::ProposerFactory::new(
+        client.clone(),
+        transaction_pool.clone(),
+        prometheus_registry.as_ref(),
+    );
+
+    let authorship_future = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+        block_import: client.clone(),
+        env: proposer,
+        client,
+        pool: transaction_pool.pool().clone(),
+        commands_stream: stream::select(commands_stream, pool_commands),
+        select_chain,
+        consensus_data_provider: None,
+        inherent_data_providers,
+    });
+
+    task_manager
+        .spawn_essential_handle()
+        .spawn_blocking("manual-seal", authorship_future);
+
+    network_starter.start_network();
+    Ok((task_manager, rpc_handlers, network))
+}
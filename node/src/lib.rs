@@ -3,13 +3,21 @@ use sc_executor::native_executor_instance;
 use sc_service::{config::MultiaddrWithPeerId, ChainType};
 use sp_core::{crypto::UncheckedInto, sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
-use sunshine_node_utils::node_service;
 pub use sunshine_runtime::opaque::Block as OpaqueBlock;
 use sunshine_runtime::{
-    AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SystemConfig,
-    WASM_BINARY,
+    opaque::SessionKeys, AccountId, ArbiterMembershipConfig, AuraConfig, BalancesConfig,
+    CouncilConfig, CouncilMembershipConfig, DemocracyConfig, GenesisConfig, GrandpaConfig,
+    IndicesConfig, SessionConfig, Signature, StorageVersionConfig, SystemConfig,
+    ValidatorSetConfig, WASM_BINARY,
 };
 
+mod dev;
+mod rpc;
+mod service;
+
+pub use dev::{new_dev, Sealing};
+pub use service::{new_full, new_light, new_partial};
+
 pub const IMPL_NAME: &str = "Sunshine Node";
 pub const IMPL_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
@@ -22,12 +30,7 @@ native_executor_instance!(
     pub Executor,
     sunshine_runtime::api::dispatch,
     sunshine_runtime::native_version,
-);
-
-node_service!(
-    sunshine_runtime::opaque::Block,
-    sunshine_runtime::RuntimeApi,
-    Executor
+    frame_benchmarking::benchmarking::HostFunctions,
 );
 
 /// Specialized `ChainSpec`.
@@ -216,14 +219,45 @@ fn testnet_genesis(
                 .map(|k| (k, 1 << 60))
                 .collect(),
         }),
+        // The aura and grandpa authorities are initialized by the session.
         pallet_aura: Some(AuraConfig {
-            authorities: initial_authorities.iter().map(|x| (x.2.clone())).collect(),
+            authorities: vec![],
         }),
         pallet_grandpa: Some(GrandpaConfig {
-            authorities: initial_authorities
+            authorities: vec![],
+        }),
+        // Without staking there's no stash to convert into a validator id. The
+        // validator is the controller account, which sets the session keys and
+        // is added to the validator set.
+        pallet_session: Some(SessionConfig {
+            keys: initial_authorities
                 .iter()
-                .map(|x| (x.3.clone(), 1))
+                .map(|x| {
+                    let keys = SessionKeys {
+                        aura: x.2.clone(),
+                        grandpa: x.3.clone(),
+                    };
+                    (x.1.clone(), x.1.clone(), keys)
+                })
                 .collect(),
         }),
+        sunshine_validator_set_pallet: Some(ValidatorSetConfig {
+            validators: initial_authorities.iter().map(|x| x.1.clone()).collect(),
+        }),
+        // The authorities are the initial arbiters of escrow disputes.
+        pallet_membership_Instance1: Some(ArbiterMembershipConfig {
+            members: initial_authorities.iter().map(|x| x.1.clone()).collect(),
+            phantom: Default::default(),
+        }),
+        pallet_treasury: Some(Default::default()),
+        // The council members are initialized by the council membership.
+        pallet_collective_Instance1: Some(CouncilConfig::default()),
+        pallet_membership_Instance2: Some(CouncilMembershipConfig {
+            members: initial_authorities.iter().map(|x| x.1.clone()).collect(),
+            phantom: Default::default(),
+        }),
+        pallet_democracy: Some(DemocracyConfig::default()),
+        pallet_indices: Some(IndicesConfig { indices: vec![] }),
+        migrations: Some(StorageVersionConfig::default()),
     }
 }
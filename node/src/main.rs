@@ -1,7 +1,11 @@
-use sc_cli::{RunCmd, Runner, RuntimeVersion, SubstrateCli};
-use sc_service::{ChainSpec, DatabaseConfig, Role};
+use crate::database::{select_database, MigrateDbCmd};
+use sc_cli::{CliConfiguration, RunCmd, RuntimeVersion, SubstrateCli};
+use sc_service::{ChainSpec, PartialComponents, Role};
 use structopt::StructOpt;
 use sunshine_codec::Multihash;
+use sunshine_node::Sealing;
+
+mod database;
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -10,11 +14,32 @@ pub struct Cli {
 
     #[structopt(flatten)]
     pub run: RunCmd,
+
+    /// Seal blocks on demand instead of running aura and grandpa.
+    #[structopt(long = "sealing", possible_values = &["instant", "manual"])]
+    pub sealing: Option<Sealing>,
 }
 
 #[derive(Debug, StructOpt)]
 pub enum Subcommand {
+    /// Build a chain specification.
+    BuildSpec(sc_cli::BuildSpecCmd),
+    /// Validate blocks.
+    CheckBlock(sc_cli::CheckBlockCmd),
+    /// Export blocks.
+    ExportBlocks(sc_cli::ExportBlocksCmd),
+    /// Export the state of a given block into a chain spec.
+    ExportState(sc_cli::ExportStateCmd),
+    /// Import blocks.
+    ImportBlocks(sc_cli::ImportBlocksCmd),
+    /// Remove the whole chain.
     PurgeChain(sc_cli::PurgeChainCmd),
+    /// Revert the chain to a previous state.
+    Revert(sc_cli::RevertCmd),
+    /// Copy the chain database to another database backend.
+    MigrateDb(MigrateDbCmd),
+    /// Benchmark runtime pallets.
+    Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 }
 
 impl SubstrateCli for Cli {
@@ -50,6 +75,7 @@ impl SubstrateCli for Cli {
         Ok(match id {
             "dev" => Box::new(sunshine_node::dev_chain_spec()),
             "" | "local" => Box::new(sunshine_node::local_chain_spec()),
+            "staging" => Box::new(sunshine_node::staging_chain_spec()),
             path => Box::new(sunshine_node::ChainSpec::from_json_file(path.into())?),
         })
     }
@@ -62,27 +88,107 @@ impl SubstrateCli for Cli {
 fn main() -> sc_cli::Result<()> {
     let cli = <Cli as SubstrateCli>::from_args();
     match &cli.subcommand {
+        Some(Subcommand::BuildSpec(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+        }
+        Some(Subcommand::CheckBlock(cmd)) => {
+            let mut runner = cli.create_runner(cmd)?;
+            select_database(&mut runner, cmd.database()?);
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    import_queue,
+                    ..
+                } = sunshine_node::new_partial(&config)?;
+                Ok((cmd.run(client, import_queue), task_manager))
+            })
+        }
+        Some(Subcommand::ExportBlocks(cmd)) => {
+            let mut runner = cli.create_runner(cmd)?;
+            select_database(&mut runner, cmd.database()?);
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    ..
+                } = sunshine_node::new_partial(&config)?;
+                Ok((cmd.run(client, config.database), task_manager))
+            })
+        }
+        Some(Subcommand::ExportState(cmd)) => {
+            let mut runner = cli.create_runner(cmd)?;
+            select_database(&mut runner, cmd.database()?);
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    ..
+                } = sunshine_node::new_partial(&config)?;
+                Ok((cmd.run(client, config.chain_spec), task_manager))
+            })
+        }
+        Some(Subcommand::ImportBlocks(cmd)) => {
+            let mut runner = cli.create_runner(cmd)?;
+            select_database(&mut runner, cmd.database()?);
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    import_queue,
+                    ..
+                } = sunshine_node::new_partial(&config)?;
+                Ok((cmd.run(client, import_queue), task_manager))
+            })
+        }
         Some(Subcommand::PurgeChain(cmd)) => {
             let mut runner = cli.create_runner(cmd)?;
-            force_parity_db(&mut runner);
+            select_database(&mut runner, cmd.database()?);
             runner.sync_run(|config| cmd.run(config.database))
         }
+        Some(Subcommand::Revert(cmd)) => {
+            let mut runner = cli.create_runner(cmd)?;
+            select_database(&mut runner, cmd.database()?);
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    backend,
+                    ..
+                } = sunshine_node::new_partial(&config)?;
+                Ok((cmd.run(client, backend), task_manager))
+            })
+        }
+        Some(Subcommand::MigrateDb(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| cmd.run(config))
+        }
+        Some(Subcommand::Benchmark(cmd)) => {
+            if cfg!(feature = "runtime-benchmarks") {
+                let runner = cli.create_runner(cmd)?;
+                runner.sync_run(|config| {
+                    cmd.run::<sunshine_runtime::Block, sunshine_node::Executor>(config)
+                })
+            } else {
+                Err("Benchmarking wasn't enabled when building the node. \
+                     You can enable it with `--features runtime-benchmarks`."
+                    .into())
+            }
+        }
         None => {
             let mut runner = cli.create_runner(&cli.run)?;
-            force_parity_db(&mut runner);
+            select_database(&mut runner, cli.run.database()?);
             runner.run_node_until_exit(|config| {
+                if let Some(sealing) = cli.sealing {
+                    return sunshine_node::new_dev(config, sealing).map(|service| service.0);
+                }
                 match config.role {
                     Role::Light => sunshine_node::new_light::<Multihash>(config),
-                    _ => sunshine_node::new_full::<Multihash>(config),
+                    _ => sunshine_node::new_full(config),
                 }
                 .map(|service| service.0)
             })
         }
     }
 }
-
-fn force_parity_db(runner: &mut Runner<Cli>) {
-    let config = runner.config_mut();
-    let path = config.database.path().unwrap().to_path_buf();
-    config.database = DatabaseConfig::ParityDb { path };
-}
@@ -0,0 +1,20 @@
+//! Json rpc of the runtime apis of the sunshine pallets.
+//!
+//! The full and the development node serve the same runtime api rpc next to
+//! the rpc of substrate.
+use crate::Executor;
+use jsonrpc_core::{MetaIoHandler, Metadata};
+use std::sync::Arc;
+use sunshine_escrow_rpc::{Bounty, BountyApi};
+use sunshine_identity_rpc::{Identity, IdentityApi};
+use sunshine_runtime::opaque::Block;
+use sunshine_runtime::{Cid, RuntimeApi};
+
+/// Client of a full node.
+pub type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
+
+/// Registers the rpc of the runtime apis with `io`.
+pub fn extend_with_runtime_apis<M: Metadata>(io: &mut MetaIoHandler<M>, client: Arc<FullClient>) {
+    io.extend_with(BountyApi::to_delegate(Bounty::new(client.clone())));
+    io.extend_with(IdentityApi::to_delegate(Identity::<_, _, Cid>::new(client)));
+}
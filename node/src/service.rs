@@ -0,0 +1,176 @@
+//! Services of the full and the light node.
+//!
+//! The light node and the components shared with the chain subcommands are
+//! generated by `node_service!`. The full node is built here, so that it can
+//! serve the rpc of the runtime apis.
+use crate::rpc::extend_with_runtime_apis;
+use sc_finality_grandpa::{FinalityProofProvider, SharedVoterState};
+use sc_network::NetworkService;
+use sc_service::{error::Error as ServiceError, Configuration, RpcHandlers, TaskManager};
+use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+use std::time::Duration;
+use sunshine_runtime::opaque::Block;
+
+/// The full node of `node_service!` is replaced by [`new_full`].
+#[allow(dead_code)]
+mod generated {
+    use sunshine_node_utils::node_service;
+
+    node_service!(
+        sunshine_runtime::opaque::Block,
+        sunshine_runtime::RuntimeApi,
+        crate::Executor
+    );
+}
+
+pub use generated::{new_light, new_partial};
+
+/// Builds a full node that authors blocks with aura and votes on finality with
+/// grandpa when it is an authority.
+#[allow(clippy::type_complexity)]
+pub fn new_full(
+    config: Configuration,
+) -> Result<
+    (
+        TaskManager,
+        RpcHandlers,
+        Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
+    ),
+    ServiceError,
+> {
+    let sc_service::PartialComponents {
+        client,
+        backend,
+        mut task_manager,
+        import_queue,
+        keystore,
+        select_chain,
+        transaction_pool,
+        inherent_data_providers,
+        other: (block_import, grandpa_link),
+    } = new_partial(&config)?;
+
+    let finality_proof_provider =
+        FinalityProofProvider::new_for_service(backend.clone(), client.clone());
+
+    let (network, network_status_sinks, system_rpc_tx, network_starter) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            on_demand: None,
+            block_announce_validator_builder: None,
+            finality_proof_request_builder: None,
+            finality_proof_provider: Some(finality_proof_provider),
+        })?;
+
+    if config.offchain_worker.enabled {
+        sc_service::build_offchain_workers(
+            &config,
+            backend.clone(),
+            task_manager.spawn_handle(),
+            client.clone(),
+            network.clone(),
+        );
+    }
+
+    let role = config.role.clone();
+    let force_authoring = config.force_authoring;
+    let name = config.network.node_name.clone();
+    let enable_grandpa = !config.disable_grandpa;
+    let prometheus_registry = config.prometheus_registry().cloned();
+    let telemetry_connection_sinks = sc_service::TelemetryConnectionSinks::default();
+
+    let rpc_client = client.clone();
+    let rpc_extensions_builder = Box::new(move |_| {
+        let mut io = jsonrpc_core::IoHandler::default();
+        extend_with_runtime_apis(&mut io, rpc_client.clone());
+        io
+    });
+
+    let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        network: network.clone(),
+        client: client.clone(),
+        keystore: keystore.clone(),
+        task_manager: &mut task_manager,
+        transaction_pool: transaction_pool.clone(),
+        telemetry_connection_sinks: telemetry_connection_sinks.clone(),
+        rpc_extensions_builder,
+        on_demand: None,
+        remote_blockchain: None,
+        backend,
+        network_status_sinks,
+        system_rpc_tx,
+        config,
+    })?;
+
+    if role.is_authority() {
+        let proposer = sc_basic_authorship::ProposerFactory::new(
+            client.clone(),
+            transaction_pool,
+            prometheus_registry.as_ref(),
+        );
+        let can_author_with =
+            sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone());
+        let aura = sc_consensus_aura::start_aura::<_, _, _, _, _, AuraPair, _, _, _>(
+            sc_consensus_aura::slot_duration(&*client)?,
+            client.clone(),
+            select_chain,
+            block_import,
+            proposer,
+            network.clone(),
+            inherent_data_providers.clone(),
+            force_authoring,
+            keystore.clone(),
+            can_author_with,
+        )?;
+        task_manager
+            .spawn_essential_handle()
+            .spawn_blocking("aura", aura);
+    }
+
+    // Only authorities sign finality votes.
+    let keystore = if role.is_authority() {
+        Some(keystore as sp_core::traits::BareCryptoStorePtr)
+    } else {
+        None
+    };
+    let grandpa_config = sc_finality_grandpa::Config {
+        gossip_duration: Duration::from_millis(333),
+        justification_period: 512,
+        name: Some(name),
+        observer_enabled: false,
+        keystore,
+        is_authority: role.is_network_authority(),
+    };
+
+    if enable_grandpa {
+        let grandpa_params = sc_finality_grandpa::GrandpaParams {
+            config: grandpa_config,
+            link: grandpa_link,
+            network: network.clone(),
+            inherent_data_providers,
+            telemetry_on_connect: Some(telemetry_connection_sinks.on_connect_stream()),
+            voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
+            prometheus_registry,
+            shared_voter_state: SharedVoterState::empty(),
+        };
+        task_manager.spawn_essential_handle().spawn_blocking(
+            "grandpa-voter",
+            sc_finality_grandpa::run_grandpa_voter(grandpa_params)?,
+        );
+    } else {
+        sc_finality_grandpa::setup_disabled_grandpa(
+            client,
+            &inherent_data_providers,
+            network.clone(),
+        )?;
+    }
+
+    network_starter.start_network();
+    Ok((task_manager, rpc_handlers, network))
+}
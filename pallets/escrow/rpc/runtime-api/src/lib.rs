@@ -0,0 +1,42 @@
+//! Runtime api for querying escrowed bounties.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use parity_scale_codec::Codec;
+use sp_std::prelude::*;
+pub use sunshine_escrow_pallet::{EscrowBounty, MilestoneSubmission, VestingBalance};
+
+sp_api::decl_runtime_apis! {
+    #[api_version(3)]
+    pub trait BountyApi<AccountId, IpfsReference, EscrowId, SubmissionId, Uid, Balance, BlockNumber>
+    where
+        AccountId: Codec,
+        IpfsReference: Codec,
+        EscrowId: Codec,
+        SubmissionId: Codec,
+        Uid: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+    {
+        /// Open bounties with at least `min_total` contributed, ordered by
+        /// the total contributed from highest to lowest.
+        fn open_bounties(
+            min_total: Balance,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(EscrowId, EscrowBounty<AccountId, IpfsReference, Balance, BlockNumber>)>;
+
+        /// Submissions for bounty `id`.
+        fn submissions(id: EscrowId) -> Vec<(SubmissionId, MilestoneSubmission<AccountId, IpfsReference>)>;
+
+        /// Contributions of `account` to open bounties.
+        fn contributions(account: AccountId) -> Vec<(EscrowId, Balance)>;
+
+        /// Submissions of team bounties waiting for an approval of `uid`,
+        /// with the uids that already approved them.
+        fn pending_approvals(uid: Uid) -> Vec<(EscrowId, SubmissionId, Vec<Uid>)>;
+
+        /// Free balance of `account` and how much of it is locked by vesting
+        /// payouts.
+        fn vesting_balance(account: AccountId) -> VestingBalance<Balance>;
+    }
+}
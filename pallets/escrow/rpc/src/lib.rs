@@ -0,0 +1,201 @@
+//! Json rpc for querying escrowed bounties.
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use parity_scale_codec::Codec;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Block as BlockT;
+use std::marker::PhantomData;
+use std::sync::Arc;
+pub use sunshine_escrow_rpc_runtime_api::BountyApi as BountyRuntimeApi;
+use sunshine_escrow_rpc_runtime_api::{EscrowBounty, MilestoneSubmission, VestingBalance};
+
+/// Maximum number of bounties returned per page.
+const MAX_LIMIT: u32 = 100;
+
+#[rpc]
+pub trait BountyApi<
+    BlockHash,
+    AccountId,
+    IpfsReference,
+    EscrowId,
+    SubmissionId,
+    Uid,
+    Balance,
+    BlockNumber,
+>
+{
+    /// Open bounties with at least `min_total` contributed, ordered by the
+    /// total contributed from highest to lowest.
+    #[rpc(name = "bounty_openBounties")]
+    fn open_bounties(
+        &self,
+        min_total: Balance,
+        offset: u32,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<
+        Vec<(
+            EscrowId,
+            EscrowBounty<AccountId, IpfsReference, Balance, BlockNumber>,
+        )>,
+    >;
+
+    /// Submissions for bounty `id`.
+    #[rpc(name = "bounty_submissions")]
+    fn submissions(
+        &self,
+        id: EscrowId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(SubmissionId, MilestoneSubmission<AccountId, IpfsReference>)>>;
+
+    /// Contributions of `account` to open bounties.
+    #[rpc(name = "bounty_contributions")]
+    fn contributions(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(EscrowId, Balance)>>;
+
+    /// Submissions of team bounties waiting for an approval of `uid`, with
+    /// the uids that already approved them.
+    #[rpc(name = "bounty_pendingApprovals")]
+    fn pending_approvals(
+        &self,
+        uid: Uid,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(EscrowId, SubmissionId, Vec<Uid>)>>;
+
+    /// Free balance of `account` and how much of it is locked by vesting
+    /// payouts.
+    #[rpc(name = "bounty_vestingBalance")]
+    fn vesting_balance(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<VestingBalance<Balance>>;
+}
+
+/// Implements the bounty rpc with the runtime api of `C`.
+pub struct Bounty<C, B> {
+    client: Arc<C>,
+    _marker: PhantomData<B>,
+}
+
+impl<C, B> Bounty<C, B> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: PhantomData,
+        }
+    }
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(1),
+        message: "Unable to query the runtime api.".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
+impl<C, Block, AccountId, IpfsReference, EscrowId, SubmissionId, Uid, Balance, BlockNumber>
+    BountyApi<
+        <Block as BlockT>::Hash,
+        AccountId,
+        IpfsReference,
+        EscrowId,
+        SubmissionId,
+        Uid,
+        Balance,
+        BlockNumber,
+    > for Bounty<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: BountyRuntimeApi<
+        Block,
+        AccountId,
+        IpfsReference,
+        EscrowId,
+        SubmissionId,
+        Uid,
+        Balance,
+        BlockNumber,
+    >,
+    AccountId: Codec,
+    IpfsReference: Codec,
+    EscrowId: Codec,
+    SubmissionId: Codec,
+    Uid: Codec,
+    Balance: Codec,
+    BlockNumber: Codec,
+{
+    fn open_bounties(
+        &self,
+        min_total: Balance,
+        offset: u32,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<
+        Vec<(
+            EscrowId,
+            EscrowBounty<AccountId, IpfsReference, Balance, BlockNumber>,
+        )>,
+    > {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .open_bounties(&at, min_total, offset, limit.min(MAX_LIMIT))
+            .map_err(runtime_error)
+    }
+
+    fn submissions(
+        &self,
+        id: EscrowId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(SubmissionId, MilestoneSubmission<AccountId, IpfsReference>)>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .submissions(&at, id)
+            .map_err(runtime_error)
+    }
+
+    fn contributions(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(EscrowId, Balance)>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .contributions(&at, account)
+            .map_err(runtime_error)
+    }
+
+    fn pending_approvals(
+        &self,
+        uid: Uid,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(EscrowId, SubmissionId, Vec<Uid>)>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .pending_approvals(&at, uid)
+            .map_err(runtime_error)
+    }
+
+    fn vesting_balance(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<VestingBalance<Balance>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .vesting_balance(&at, account)
+            .map_err(runtime_error)
+    }
+}
@@ -0,0 +1,215 @@
+//! Benchmarks for the escrow pallet.
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+    let account = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&account, BalanceOf::<T>::max_value() / 2u32.into());
+    account
+}
+
+/// Smallest amount that is a valid deposit, contribution and milestone.
+fn min_amount<T: Trait>() -> BalanceOf<T> {
+    T::MinDeposit::get()
+        .max(T::MinContribution::get())
+        .max(T::Currency::minimum_balance())
+}
+
+/// Posts a bounty that pays `amount` for each of `milestones` milestones.
+fn post<T: Trait>(
+    milestones: u32,
+    amount: BalanceOf<T>,
+) -> Result<(T::AccountId, T::EscrowId), &'static str> {
+    let poster = funded_account::<T>("poster", 0);
+    let id = BountyNonce::<T>::get();
+    Module::<T>::post_bounty(
+        RawOrigin::Signed(poster.clone()).into(),
+        Default::default(),
+        vec![amount; milestones as usize],
+        min_amount::<T>(),
+        T::MinLifetime::get(),
+    )?;
+    Ok((poster, id))
+}
+
+/// Submits work for the first milestone of bounty `id`.
+fn submit<T: Trait>(
+    id: T::EscrowId,
+    index: u32,
+) -> Result<(T::AccountId, T::SubmissionId), &'static str> {
+    let submitter = funded_account::<T>("submitter", index);
+    let submission_id = SubmissionNonce::<T>::get();
+    Module::<T>::submit(
+        RawOrigin::Signed(submitter.clone()).into(),
+        id,
+        0,
+        Default::default(),
+    )?;
+    Ok((submitter, submission_id))
+}
+
+/// Fills bounty `id` with `count` submissions of other submitters.
+fn fill_submissions<T: Trait>(id: T::EscrowId, count: u32) -> Result<(), &'static str> {
+    for i in 1..=count {
+        submit::<T>(id, i)?;
+    }
+    Ok(())
+}
+
+benchmarks! {
+    _ { }
+
+    post_bounty {
+        let m in 1 .. T::MaxMilestones::get();
+        let poster = funded_account::<T>("poster", 0);
+        let amount = min_amount::<T>();
+    }: _(
+        RawOrigin::Signed(poster),
+        Default::default(),
+        vec![amount; m as usize],
+        amount,
+        T::MinLifetime::get()
+    )
+
+    // The poster and `c - 1` contributors already contributed.
+    contribute {
+        let c in 1 .. T::MaxContributors::get() - 1;
+        let amount = min_amount::<T>();
+        let (_, id) = post::<T>(1, amount * T::MaxContributors::get().into())?;
+        for i in 1 .. c {
+            let contributor = funded_account::<T>("contributor", i);
+            Module::<T>::contribute(RawOrigin::Signed(contributor).into(), id, amount)?;
+        }
+        let contributor = funded_account::<T>("contributor", 0);
+    }: _(RawOrigin::Signed(contributor), id, amount)
+
+    // The bounty already has `s` pending submissions.
+    submit {
+        let s in 0 .. T::MaxSubmissions::get() - 1;
+        let (_, id) = post::<T>(1, min_amount::<T>())?;
+        fill_submissions::<T>(id, s)?;
+        let submitter = funded_account::<T>("submitter", 0);
+    }: _(RawOrigin::Signed(submitter), id, 0, Default::default())
+
+    // Paying out the last milestone completes the bounty and clears its storage,
+    // including the other `MaxSubmissions - 1` submissions.
+    approve {
+        let (poster, id) = post::<T>(1, min_amount::<T>())?;
+        let (_, submission_id) = submit::<T>(id, 0)?;
+        fill_submissions::<T>(id, T::MaxSubmissions::get() - 1)?;
+    }: _(RawOrigin::Signed(poster), id, submission_id)
+
+    // The last of `a` approvers of a team bounty reaches the threshold and
+    // pays out the last milestone.
+    approve_team {
+        let a in 1 .. T::MaxApprovers::get();
+        let (poster, id) = post::<T>(1, min_amount::<T>())?;
+        let mut approvers = (0 .. a)
+            .map(|i| funded_account::<T>("approver", i))
+            .collect::<Vec<_>>();
+        let uids = approvers
+            .iter()
+            .map(|approver| T::UidLookup::uid(approver).ok_or("approver without uid"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Module::<T>::set_approvers(RawOrigin::Signed(poster).into(), id, uids, a)?;
+        let (_, submission_id) = submit::<T>(id, 0)?;
+        let approver = approvers.pop().ok_or("no approvers")?;
+        for other in approvers {
+            Module::<T>::approve(RawOrigin::Signed(other).into(), id, submission_id)?;
+        }
+    }: approve(RawOrigin::Signed(approver), id, submission_id)
+    verify {
+        assert!(Module::<T>::bounties(id).is_none());
+    }
+
+    reject {
+        let (poster, id) = post::<T>(1, min_amount::<T>())?;
+        let (_, submission_id) = submit::<T>(id, 0)?;
+    }: _(RawOrigin::Signed(poster), id, submission_id)
+
+    open_dispute {
+        let d in 0 .. T::MaxDisputes::get() - 1;
+        let (_, id) = post::<T>(1, min_amount::<T>())?;
+        for i in 1 ..= d {
+            let (submitter, submission_id) = submit::<T>(id, i)?;
+            Module::<T>::open_dispute(
+                RawOrigin::Signed(submitter).into(),
+                id,
+                submission_id,
+                Default::default(),
+            )?;
+        }
+        let (submitter, submission_id) = submit::<T>(id, 0)?;
+    }: _(RawOrigin::Signed(submitter), id, submission_id, Default::default())
+
+    // Benchmarked against the genesis arbiters, a single vote resolves the
+    // dispute on a dev chain and pays out the milestone.
+    vote_dispute {
+        let (_, id) = post::<T>(1, min_amount::<T>())?;
+        let (submitter, submission_id) = submit::<T>(id, 0)?;
+        Module::<T>::open_dispute(
+            RawOrigin::Signed(submitter).into(),
+            id,
+            submission_id,
+            Default::default(),
+        )?;
+        let arbiter = T::Arbiters::sorted_members()
+            .into_iter()
+            .next()
+            .ok_or("no arbiters")?;
+    }: _(RawOrigin::Signed(arbiter), id, submission_id, true)
+
+    set_approvers {
+        let a in 1 .. T::MaxApprovers::get();
+        let (poster, id) = post::<T>(1, min_amount::<T>())?;
+        let uids = (0 .. a).map(T::Uid::from).collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(poster), id, uids, a)
+
+    set_vesting {
+        let (poster, id) = post::<T>(1, min_amount::<T>())?;
+    }: _(RawOrigin::Signed(poster), id, T::MaxVestingPeriod::get())
+
+    vest {
+        let s in 1 .. T::MaxVestingSchedules::get();
+        let account = funded_account::<T>("submitter", 0);
+        for _ in 0 .. s {
+            Module::<T>::start_vesting(&account, min_amount::<T>(), T::MaxVestingPeriod::get())?;
+        }
+    }: _(RawOrigin::Signed(account))
+
+    // `d` of the `s` submissions are disputed.
+    expire {
+        let c in 1 .. T::MaxContributors::get();
+        let d in 0 .. T::MaxDisputes::get();
+        let s in T::MaxDisputes::get() .. T::MaxSubmissions::get();
+        let amount = min_amount::<T>();
+        let (_, id) = post::<T>(1, amount * T::MaxContributors::get().into())?;
+        for i in 1 .. c {
+            let contributor = funded_account::<T>("contributor", i);
+            Module::<T>::contribute(RawOrigin::Signed(contributor).into(), id, amount)?;
+        }
+        for i in 0 .. d {
+            let (submitter, submission_id) = submit::<T>(id, i)?;
+            Module::<T>::open_dispute(
+                RawOrigin::Signed(submitter).into(),
+                id,
+                submission_id,
+                Default::default(),
+            )?;
+        }
+        for i in d .. s {
+            submit::<T>(id, i)?;
+        }
+    }: {
+        Module::<T>::expire(id);
+    }
+    verify {
+        assert!(Module::<T>::bounties(id).is_none());
+    }
+}
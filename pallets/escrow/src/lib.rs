@@ -0,0 +1,1114 @@
+//! Escrow for bounties that pay out in milestones.
+//!
+//! A bounty is split into ordered milestones, each with the amount paid out
+//! when it is met. Contributions are held in an account derived from the
+//! `Foundation` module id. Every submission targets a milestone and the
+//! poster approves milestones in order, releasing the funds of the approved
+//! milestone to the submitter.
+//!
+//! Every bounty has a deadline. Bounties that are still open at their
+//! deadline are closed when the deadline block is initialized and the funds
+//! of unmet milestones are refunded to the contributors pro rata. Refunds
+//! that can't be transferred are handed to `Slashed`.
+//!
+//! The poster can hand approvals over to a team of identity uids, after which
+//! a submission is paid out once a threshold of the uids approved it. Any
+//! device of an approver uid can approve or reject on behalf of the uid.
+//!
+//! The poster can also make payouts vest linearly over a number of blocks.
+//! Vesting payouts are locked in the account of the submitter, which releases
+//! the funds that vested so far by calling `vest`.
+//!
+//! A submitter whose work for the next milestone is rejected or ignored can
+//! open a dispute by reserving a bond and providing evidence. The arbiters
+//! vote on the dispute and a majority either pays out the milestone and
+//! returns the bond, or rejects the submission and slashes the bond. A dispute
+//! approved for a milestone that can't be paid out anymore is dismissed and
+//! the bond is returned.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::storage::with_transaction;
+use frame_support::traits::{
+    Contains, Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, OnUnbalanced,
+    ReservableCurrency, WithdrawReason, WithdrawReasons,
+};
+use frame_support::weights::Weight;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure, transactional, Parameter,
+};
+use frame_system::ensure_signed;
+use parity_scale_codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::{
+    AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, Member, One, Saturating, Zero,
+};
+use sp_runtime::{DispatchResult, ModuleId, Perbill, RuntimeDebug, TransactionOutcome};
+use sp_std::prelude::*;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod weights;
+
+pub use weights::WeightInfo;
+
+/// Lock of the payouts that didn't vest yet.
+const VESTING_ID: LockIdentifier = *b"escrowvs";
+
+type BalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+type EscrowBountyOf<T> = EscrowBounty<
+    <T as frame_system::Trait>::AccountId,
+    <T as Trait>::IpfsReference,
+    BalanceOf<T>,
+    <T as frame_system::Trait>::BlockNumber,
+>;
+type ApproverSetOf<T> = ApproverSet<<T as Trait>::Uid>;
+type VestingOf<T> = Vesting<BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+type DisputeOf<T> =
+    Dispute<<T as frame_system::Trait>::AccountId, <T as Trait>::IpfsReference, BalanceOf<T>>;
+
+/// Resolves the identity uid a device account belongs to.
+pub trait UidLookup<AccountId, Uid> {
+    fn uid(account: &AccountId) -> Option<Uid>;
+}
+
+pub trait Trait: frame_system::Trait {
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Reference to the offchain description of bounties and submissions.
+    type IpfsReference: Parameter + Member + Default;
+    /// The currency held in escrow.
+    type Currency: ReservableCurrency<Self::AccountId>
+        + LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+    /// Identifier of an escrowed bounty.
+    type EscrowId: Parameter + AtLeast32BitUnsigned + Default + Copy;
+    /// Identifier of a milestone submission.
+    type SubmissionId: Parameter + AtLeast32BitUnsigned + Default + Copy;
+    /// Identity of a team member approving submissions.
+    type Uid: Parameter + Member + AtLeast32BitUnsigned + Default + Copy;
+    /// Resolves the uid of the account approving a submission.
+    type UidLookup: UidLookup<Self::AccountId, Self::Uid>;
+    /// Maximum number of approvers of a bounty.
+    type MaxApprovers: Get<u32>;
+    /// Maximum number of blocks a payout vests over.
+    type MaxVestingPeriod: Get<Self::BlockNumber>;
+    /// Maximum number of payouts vesting in an account at the same time.
+    type MaxVestingSchedules: Get<u32>;
+    /// Module id the escrow accounts are derived from.
+    type Foundation: Get<ModuleId>;
+    /// Minimum amount the poster has to deposit.
+    type MinDeposit: Get<BalanceOf<Self>>;
+    /// Minimum amount of a contribution.
+    type MinContribution: Get<BalanceOf<Self>>;
+    /// Maximum number of milestones per bounty.
+    type MaxMilestones: Get<u32>;
+    /// Minimum number of blocks a bounty stays open.
+    type MinLifetime: Get<Self::BlockNumber>;
+    /// Maximum number of blocks a bounty stays open.
+    type MaxLifetime: Get<Self::BlockNumber>;
+    /// Maximum number of contributors of a bounty, including the poster.
+    type MaxContributors: Get<u32>;
+    /// Maximum number of open disputes of a bounty.
+    type MaxDisputes: Get<u32>;
+    /// Maximum number of pending submissions of a bounty. Has to be at least
+    /// `MaxDisputes`, since every dispute keeps its submission.
+    type MaxSubmissions: Get<u32>;
+    /// Maximum number of bounties expiring in a block. The remaining
+    /// bounties expire in the next block.
+    type MaxExpirations: Get<u32>;
+    /// Bond reserved from a submitter while a dispute is open.
+    type DisputeBond: Get<BalanceOf<Self>>;
+    /// Accounts that vote on disputes.
+    type Arbiters: Contains<Self::AccountId>;
+    /// Handler for slashed dispute bonds and refunds that can't be
+    /// transferred.
+    type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// Weight information for the extrinsics of the pallet.
+    type WeightInfo: WeightInfo;
+}
+
+/// A bounty split into ordered milestones.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct EscrowBounty<AccountId, IpfsReference, Balance, BlockNumber> {
+    /// Account that posted the bounty and approves milestones.
+    pub poster: AccountId,
+    /// Reference to the bounty description.
+    pub description: IpfsReference,
+    /// Amount paid out for each milestone, in order.
+    pub milestones: Vec<Balance>,
+    /// Index of the next milestone to be approved.
+    pub next_milestone: u32,
+    /// Total amount contributed to the bounty.
+    pub total: Balance,
+    /// Block at which unmet milestones are refunded.
+    pub deadline: BlockNumber,
+}
+
+impl<AccountId, IpfsReference, Balance: AtLeast32BitUnsigned + Copy, BlockNumber>
+    EscrowBounty<AccountId, IpfsReference, Balance, BlockNumber>
+{
+    /// Sum of all milestone amounts.
+    pub fn target(&self) -> Balance {
+        self.milestones
+            .iter()
+            .fold(Zero::zero(), |acc: Balance, amount| {
+                acc.saturating_add(*amount)
+            })
+    }
+}
+
+/// Work submitted for a milestone of a bounty.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MilestoneSubmission<AccountId, IpfsReference> {
+    /// Account that gets paid when the submission is approved.
+    pub submitter: AccountId,
+    /// Index of the milestone the submission is for.
+    pub milestone: u32,
+    /// Reference to the submission description.
+    pub submission: IpfsReference,
+    /// Whether the poster rejected the submission.
+    pub rejected: bool,
+}
+
+/// Uids that approve the submissions of a bounty instead of the poster.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ApproverSet<Uid> {
+    /// Uids that may approve submissions, sorted.
+    pub uids: Vec<Uid>,
+    /// Number of approvals that pay out a submission.
+    pub threshold: u32,
+}
+
+/// A payout that unlocks linearly.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct VestingSchedule<Balance, BlockNumber> {
+    /// Amount locked at `start`.
+    pub locked: Balance,
+    /// Block the payout starts vesting at.
+    pub start: BlockNumber,
+    /// Number of blocks until the payout vested.
+    pub period: BlockNumber,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy, BlockNumber: AtLeast32BitUnsigned + Copy>
+    VestingSchedule<Balance, BlockNumber>
+{
+    /// Amount that is still locked at block `now`.
+    pub fn locked_at(&self, now: BlockNumber) -> Balance {
+        let elapsed = now.saturating_sub(self.start);
+        if elapsed >= self.period {
+            return Zero::zero();
+        }
+        self.locked.saturating_sub(
+            Perbill::from_rational_approximation(elapsed, self.period) * self.locked,
+        )
+    }
+}
+
+/// The vesting payouts of an account.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Vesting<Balance, BlockNumber> {
+    /// Payouts that didn't fully vest yet.
+    pub schedules: Vec<VestingSchedule<Balance, BlockNumber>>,
+    /// Amount of the lock, as of the last time it was updated.
+    pub locked: Balance,
+}
+
+/// Balance of an account receiving vesting payouts.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct VestingBalance<Balance> {
+    /// Free balance, including the locked amount.
+    pub free: Balance,
+    /// Amount that didn't vest yet.
+    pub locked: Balance,
+    /// Amount that vested but is still locked until `vest` is called.
+    pub vested: Balance,
+}
+
+/// A dispute about a submission the poster didn't approve.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct Dispute<AccountId, IpfsReference, Balance> {
+    /// Bond reserved from the submitter.
+    pub bond: Balance,
+    /// Reference to the evidence of the submitter.
+    pub evidence: IpfsReference,
+    /// Arbiters that voted to pay out the milestone.
+    pub ayes: Vec<AccountId>,
+    /// Arbiters that voted to reject the submission.
+    pub nays: Vec<AccountId>,
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Escrow {
+        BountyNonce: T::EscrowId;
+        SubmissionNonce: T::SubmissionId;
+
+        pub Bounties get(fn bounties): map
+            hasher(blake2_128_concat) T::EscrowId
+            => Option<EscrowBountyOf<T>>;
+        pub Contributions get(fn contributions): double_map
+            hasher(blake2_128_concat) T::EscrowId,
+            hasher(blake2_128_concat) T::AccountId
+            => BalanceOf<T>;
+        pub Submissions get(fn submissions): double_map
+            hasher(blake2_128_concat) T::EscrowId,
+            hasher(blake2_128_concat) T::SubmissionId
+            => Option<MilestoneSubmission<T::AccountId, T::IpfsReference>>;
+        pub Disputes get(fn disputes): double_map
+            hasher(blake2_128_concat) T::EscrowId,
+            hasher(blake2_128_concat) T::SubmissionId
+            => Option<DisputeOf<T>>;
+        /// Approvers of team bounties.
+        pub Approvers get(fn approvers): map
+            hasher(blake2_128_concat) T::EscrowId => Option<ApproverSetOf<T>>;
+        /// Uids that approved a submission of a team bounty.
+        pub Approvals get(fn approvals): double_map
+            hasher(blake2_128_concat) T::EscrowId,
+            hasher(blake2_128_concat) T::SubmissionId
+            => Vec<T::Uid>;
+        /// Number of blocks the payouts of a bounty vest over.
+        pub VestingPeriods get(fn vesting_period): map
+            hasher(blake2_128_concat) T::EscrowId => Option<T::BlockNumber>;
+        /// Vesting payouts of an account.
+        pub VestingSchedules get(fn vesting): map
+            hasher(blake2_128_concat) T::AccountId => Option<VestingOf<T>>;
+        /// Bounties that expire at the start of a block.
+        pub Expirations get(fn expirations): map
+            hasher(twox_64_concat) T::BlockNumber => Vec<T::EscrowId>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        <T as frame_system::Trait>::AccountId,
+        <T as frame_system::Trait>::BlockNumber,
+        <T as Trait>::IpfsReference,
+        <T as Trait>::EscrowId,
+        <T as Trait>::SubmissionId,
+        <T as Trait>::Uid,
+        Balance = BalanceOf<T>,
+    {
+        /// A bounty was posted. \[id, poster, deposit, description\]
+        EscrowPosted(EscrowId, AccountId, Balance, IpfsReference),
+        /// Funds were contributed to a bounty. \[id, contributor, amount, total\]
+        EscrowContribution(EscrowId, AccountId, Balance, Balance),
+        /// Work was submitted for a milestone. \[id, submission, milestone, submitter, reference\]
+        MilestoneSubmitted(EscrowId, SubmissionId, u32, AccountId, IpfsReference),
+        /// A milestone was approved and paid out. \[id, submission, milestone, submitter, amount\]
+        MilestonePaid(EscrowId, SubmissionId, u32, AccountId, Balance),
+        /// All milestones of a bounty were paid out. \[id\]
+        EscrowCompleted(EscrowId),
+        /// A contribution was refunded after the deadline. \[id, contributor, amount\]
+        EscrowRefunded(EscrowId, AccountId, Balance),
+        /// A bounty reached its deadline before all milestones were paid out. \[id\]
+        EscrowExpired(EscrowId),
+        /// The poster rejected a submission. \[id, submission\]
+        MilestoneRejected(EscrowId, SubmissionId),
+        /// A submitter disputed a submission. \[id, submission, submitter, bond, evidence\]
+        DisputeOpened(EscrowId, SubmissionId, AccountId, Balance, IpfsReference),
+        /// An arbiter voted on a dispute. \[id, submission, arbiter, approve\]
+        DisputeVoted(EscrowId, SubmissionId, AccountId, bool),
+        /// The arbiters resolved a dispute. \[id, submission, approved\]
+        DisputeResolved(EscrowId, SubmissionId, bool),
+        /// The poster handed approvals over to a team. \[id, threshold, uids\]
+        ApproversSet(EscrowId, u32, Vec<Uid>),
+        /// An approver approved a submission. \[id, submission, uid, approvals\]
+        MilestoneApproved(EscrowId, SubmissionId, Uid, u32),
+        /// The payouts of a bounty vest over a number of blocks. \[id, period\]
+        VestingSet(EscrowId, BlockNumber),
+        /// A payout started vesting. \[account, amount, period\]
+        VestingStarted(AccountId, Balance, BlockNumber),
+        /// The vested funds of an account were unlocked. \[account, locked\]
+        Vested(AccountId, Balance),
+        /// The arbiters approved a dispute whose milestone can't be paid out,
+        /// which returned the bond. \[id, submission\]
+        DisputeDismissed(EscrowId, SubmissionId),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// A bounty needs at least one milestone.
+        NoMilestones,
+        /// A bounty has more than `MaxMilestones` milestones.
+        TooManyMilestones,
+        /// A milestone doesn't pay anything.
+        ZeroMilestone,
+        /// The deposit is smaller than `MinDeposit`.
+        DepositTooLow,
+        /// The contribution is smaller than `MinContribution`.
+        ContributionTooLow,
+        /// The contribution exceeds the sum of all milestones.
+        ExceedsTarget,
+        /// An amount overflowed.
+        Overflow,
+        /// There is no bounty with this id.
+        UnknownBounty,
+        /// There is no submission with this id.
+        UnknownSubmission,
+        /// The milestone doesn't exist or was already paid out.
+        InvalidMilestone,
+        /// Only the poster can approve milestones.
+        NotPoster,
+        /// Milestones have to be approved in order.
+        MilestoneOutOfOrder,
+        /// The escrow doesn't hold enough funds to pay the milestone.
+        InsufficientFunds,
+        /// The lifetime is shorter than `MinLifetime`.
+        LifetimeTooShort,
+        /// The lifetime is longer than `MaxLifetime`.
+        LifetimeTooLong,
+        /// Only the submitter can dispute a submission.
+        NotSubmitter,
+        /// The submission is already disputed.
+        AlreadyDisputed,
+        /// There is no dispute for this submission.
+        UnknownDispute,
+        /// Only arbiters can vote on disputes.
+        NotArbiter,
+        /// The arbiter already voted on the dispute.
+        AlreadyVoted,
+        /// The bounty already has approvers.
+        ApproversAlreadySet,
+        /// A bounty has more than `MaxApprovers` approvers.
+        TooManyApprovers,
+        /// The threshold is zero or larger than the number of approvers.
+        InvalidThreshold,
+        /// The account doesn't belong to an approver uid of the bounty.
+        NotApprover,
+        /// The uid already approved the submission.
+        AlreadyApproved,
+        /// The payouts of the bounty already vest.
+        VestingAlreadySet,
+        /// The vesting period is zero or longer than `MaxVestingPeriod`.
+        InvalidVestingPeriod,
+        /// The account has `MaxVestingSchedules` payouts vesting.
+        TooManyVestingSchedules,
+        /// The account doesn't have any vesting payouts.
+        NotVesting,
+        /// The submission was rejected.
+        SubmissionRejected,
+        /// The bounty has `MaxContributors` contributors.
+        TooManyContributors,
+        /// The bounty has `MaxDisputes` open disputes.
+        TooManyDisputes,
+        /// The bounty has `MaxSubmissions` pending submissions.
+        TooManySubmissions,
+        /// The last milestone is below the existential deposit, which the
+        /// escrow account keeps until the last milestone is paid out.
+        LastMilestoneTooLow,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        const Foundation: ModuleId = T::Foundation::get();
+        const MinDeposit: BalanceOf<T> = T::MinDeposit::get();
+        const MinContribution: BalanceOf<T> = T::MinContribution::get();
+        const MaxMilestones: u32 = T::MaxMilestones::get();
+        const MinLifetime: T::BlockNumber = T::MinLifetime::get();
+        const MaxLifetime: T::BlockNumber = T::MaxLifetime::get();
+        const MaxContributors: u32 = T::MaxContributors::get();
+        const MaxDisputes: u32 = T::MaxDisputes::get();
+        const MaxSubmissions: u32 = T::MaxSubmissions::get();
+        const MaxExpirations: u32 = T::MaxExpirations::get();
+        const DisputeBond: BalanceOf<T> = T::DisputeBond::get();
+        const MaxApprovers: u32 = T::MaxApprovers::get();
+        const MaxVestingPeriod: T::BlockNumber = T::MaxVestingPeriod::get();
+        const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            let mut ids = Expirations::<T>::take(n);
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            let max = T::MaxExpirations::get() as usize;
+            if ids.len() > max {
+                // The bounties that don't fit expire before the ones of the
+                // next block.
+                let mut rest = ids.split_off(max);
+                Expirations::<T>::mutate(n.saturating_add(One::one()), |next| {
+                    rest.append(next);
+                    *next = rest;
+                });
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+            }
+            ids.into_iter().fold(weight, |weight, id| weight.saturating_add(Self::expire(id)))
+        }
+
+        /// Posts a bounty with the amount of each milestone and deposits the
+        /// first funds into escrow. The bounty expires `lifetime` blocks from
+        /// now.
+        #[weight = T::WeightInfo::post_bounty(milestones.len() as u32)]
+        fn post_bounty(
+            origin,
+            description: T::IpfsReference,
+            milestones: Vec<BalanceOf<T>>,
+            deposit: BalanceOf<T>,
+            lifetime: T::BlockNumber,
+        ) -> DispatchResult {
+            let poster = ensure_signed(origin)?;
+            ensure!(lifetime >= T::MinLifetime::get(), Error::<T>::LifetimeTooShort);
+            ensure!(lifetime <= T::MaxLifetime::get(), Error::<T>::LifetimeTooLong);
+            ensure!(!milestones.is_empty(), Error::<T>::NoMilestones);
+            ensure!(
+                milestones.len() as u32 <= T::MaxMilestones::get(),
+                Error::<T>::TooManyMilestones
+            );
+            ensure!(milestones.iter().all(|amount| !amount.is_zero()), Error::<T>::ZeroMilestone);
+            ensure!(
+                milestones
+                    .last()
+                    .map_or(false, |amount| *amount >= T::Currency::minimum_balance()),
+                Error::<T>::LastMilestoneTooLow
+            );
+            ensure!(deposit >= T::MinDeposit::get(), Error::<T>::DepositTooLow);
+            let target = milestones
+                .iter()
+                .try_fold(BalanceOf::<T>::zero(), |acc, amount| acc.checked_add(amount))
+                .ok_or(Error::<T>::Overflow)?;
+            ensure!(deposit <= target, Error::<T>::ExceedsTarget);
+
+            let id = BountyNonce::<T>::get();
+            T::Currency::transfer(
+                &poster,
+                &Self::account_id(id),
+                deposit,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            let deadline = <frame_system::Module<T>>::block_number().saturating_add(lifetime);
+            BountyNonce::<T>::put(id + One::one());
+            Expirations::<T>::append(deadline, id);
+            Contributions::<T>::insert(id, &poster, deposit);
+            Bounties::<T>::insert(id, EscrowBounty {
+                poster: poster.clone(),
+                description: description.clone(),
+                milestones,
+                next_milestone: 0,
+                total: deposit,
+                deadline,
+            });
+            Self::deposit_event(RawEvent::EscrowPosted(id, poster, deposit, description));
+            Ok(())
+        }
+
+        /// Contributes funds to a bounty.
+        #[weight = T::WeightInfo::contribute(T::MaxContributors::get())]
+        fn contribute(origin, id: T::EscrowId, amount: BalanceOf<T>) -> DispatchResult {
+            let contributor = ensure_signed(origin)?;
+            ensure!(amount >= T::MinContribution::get(), Error::<T>::ContributionTooLow);
+            let mut bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            let total = bounty.total.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+            ensure!(total <= bounty.target(), Error::<T>::ExceedsTarget);
+            ensure!(
+                Contributions::<T>::contains_key(id, &contributor)
+                    || (Contributions::<T>::iter_prefix(id).count() as u32)
+                        < T::MaxContributors::get(),
+                Error::<T>::TooManyContributors
+            );
+
+            T::Currency::transfer(
+                &contributor,
+                &Self::account_id(id),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            Contributions::<T>::mutate(id, &contributor, |c| *c = c.saturating_add(amount));
+            bounty.total = total;
+            Bounties::<T>::insert(id, bounty);
+            Self::deposit_event(RawEvent::EscrowContribution(id, contributor, amount, total));
+            Ok(())
+        }
+
+        /// Submits work for a milestone that wasn't paid out yet.
+        #[weight = T::WeightInfo::submit(T::MaxSubmissions::get())]
+        fn submit(
+            origin,
+            id: T::EscrowId,
+            milestone: u32,
+            submission: T::IpfsReference,
+        ) -> DispatchResult {
+            let submitter = ensure_signed(origin)?;
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            ensure!(
+                milestone >= bounty.next_milestone
+                    && (milestone as usize) < bounty.milestones.len(),
+                Error::<T>::InvalidMilestone
+            );
+            ensure!(
+                (Submissions::<T>::iter_prefix(id).count() as u32) < T::MaxSubmissions::get(),
+                Error::<T>::TooManySubmissions
+            );
+
+            let submission_id = SubmissionNonce::<T>::get();
+            SubmissionNonce::<T>::put(submission_id + One::one());
+            Submissions::<T>::insert(id, submission_id, MilestoneSubmission {
+                submitter: submitter.clone(),
+                milestone,
+                submission: submission.clone(),
+                rejected: false,
+            });
+            Self::deposit_event(RawEvent::MilestoneSubmitted(
+                id,
+                submission_id,
+                milestone,
+                submitter,
+                submission,
+            ));
+            Ok(())
+        }
+
+        /// Approves a submission for the next milestone and pays it out.
+        ///
+        /// Submissions of team bounties are paid out once the threshold of
+        /// approver uids approved them.
+        #[weight = T::WeightInfo::approve()
+            .max(T::WeightInfo::approve_team(T::MaxApprovers::get()))]
+        #[transactional]
+        fn approve(origin, id: T::EscrowId, submission_id: T::SubmissionId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            let submission =
+                Self::submissions(id, submission_id).ok_or(Error::<T>::UnknownSubmission)?;
+            ensure!(!submission.rejected, Error::<T>::SubmissionRejected);
+            let approvers = match Self::approvers(id) {
+                Some(approvers) => approvers,
+                None => {
+                    ensure!(bounty.poster == who, Error::<T>::NotPoster);
+                    return Self::pay_milestone(id, bounty, submission_id);
+                }
+            };
+            let uid = Self::approver_uid(&approvers, &who)?;
+            ensure!(
+                submission.milestone == bounty.next_milestone,
+                Error::<T>::MilestoneOutOfOrder
+            );
+            let mut approvals = Self::approvals(id, submission_id);
+            ensure!(!approvals.contains(&uid), Error::<T>::AlreadyApproved);
+
+            approvals.push(uid);
+            let count = approvals.len() as u32;
+            if count >= approvers.threshold {
+                Self::pay_milestone(id, bounty, submission_id)?;
+            } else {
+                Approvals::<T>::insert(id, submission_id, approvals);
+            }
+            Self::deposit_event(RawEvent::MilestoneApproved(id, submission_id, uid, count));
+            Ok(())
+        }
+
+        /// Rejects a submission, which allows the submitter to dispute it.
+        ///
+        /// Any approver of a team bounty can reject a submission.
+        #[weight = T::WeightInfo::reject()]
+        fn reject(origin, id: T::EscrowId, submission_id: T::SubmissionId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            if let Some(approvers) = Self::approvers(id) {
+                Self::approver_uid(&approvers, &who)?;
+            } else {
+                ensure!(bounty.poster == who, Error::<T>::NotPoster);
+            }
+            let mut submission =
+                Self::submissions(id, submission_id).ok_or(Error::<T>::UnknownSubmission)?;
+            ensure!(
+                !Disputes::<T>::contains_key(id, submission_id),
+                Error::<T>::AlreadyDisputed
+            );
+
+            submission.rejected = true;
+            Submissions::<T>::insert(id, submission_id, submission);
+            Approvals::<T>::remove(id, submission_id);
+            Self::deposit_event(RawEvent::MilestoneRejected(id, submission_id));
+            Ok(())
+        }
+
+        /// Disputes a submission for the next milestone that was rejected or
+        /// wasn't approved by the poster.
+        #[weight = T::WeightInfo::open_dispute(T::MaxDisputes::get())]
+        fn open_dispute(
+            origin,
+            id: T::EscrowId,
+            submission_id: T::SubmissionId,
+            evidence: T::IpfsReference,
+        ) -> DispatchResult {
+            let submitter = ensure_signed(origin)?;
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            let submission =
+                Self::submissions(id, submission_id).ok_or(Error::<T>::UnknownSubmission)?;
+            ensure!(submission.submitter == submitter, Error::<T>::NotSubmitter);
+            ensure!(
+                submission.milestone == bounty.next_milestone,
+                Error::<T>::MilestoneOutOfOrder
+            );
+            ensure!(
+                !Disputes::<T>::contains_key(id, submission_id),
+                Error::<T>::AlreadyDisputed
+            );
+            ensure!(
+                (Disputes::<T>::iter_prefix(id).count() as u32) < T::MaxDisputes::get(),
+                Error::<T>::TooManyDisputes
+            );
+
+            let bond = T::DisputeBond::get();
+            T::Currency::reserve(&submitter, bond)?;
+            Disputes::<T>::insert(id, submission_id, Dispute {
+                bond,
+                evidence: evidence.clone(),
+                ayes: Vec::new(),
+                nays: Vec::new(),
+            });
+            Self::deposit_event(RawEvent::DisputeOpened(
+                id,
+                submission_id,
+                submitter,
+                bond,
+                evidence,
+            ));
+            Ok(())
+        }
+
+        /// Votes on a dispute. The dispute is resolved once a majority of the
+        /// arbiters voted the same way.
+        #[weight = T::WeightInfo::vote_dispute()]
+        #[transactional]
+        fn vote_dispute(
+            origin,
+            id: T::EscrowId,
+            submission_id: T::SubmissionId,
+            approve: bool,
+        ) -> DispatchResult {
+            let arbiter = ensure_signed(origin)?;
+            ensure!(T::Arbiters::contains(&arbiter), Error::<T>::NotArbiter);
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            let mut dispute =
+                Self::disputes(id, submission_id).ok_or(Error::<T>::UnknownDispute)?;
+            ensure!(
+                !dispute.ayes.contains(&arbiter) && !dispute.nays.contains(&arbiter),
+                Error::<T>::AlreadyVoted
+            );
+
+            if approve {
+                dispute.ayes.push(arbiter.clone());
+            } else {
+                dispute.nays.push(arbiter.clone());
+            }
+            Self::deposit_event(RawEvent::DisputeVoted(
+                id,
+                submission_id,
+                arbiter,
+                approve,
+            ));
+
+            let threshold = T::Arbiters::count() / 2 + 1;
+            if dispute.ayes.len() >= threshold {
+                // Paying out the milestone returns the bond. Failing the vote
+                // when the milestone was paid out for another submission or
+                // the escrow lacks the funds would keep the bond reserved.
+                let paid = with_transaction(|| {
+                    match Self::pay_milestone(id, bounty, submission_id) {
+                        Ok(()) => TransactionOutcome::Commit(true),
+                        Err(_) => TransactionOutcome::Rollback(false),
+                    }
+                });
+                if paid {
+                    Self::deposit_event(RawEvent::DisputeResolved(id, submission_id, true));
+                } else {
+                    let submission = Self::submissions(id, submission_id)
+                        .ok_or(Error::<T>::UnknownSubmission)?;
+                    T::Currency::unreserve(&submission.submitter, dispute.bond);
+                    Disputes::<T>::remove(id, submission_id);
+                    Self::deposit_event(RawEvent::DisputeDismissed(id, submission_id));
+                }
+            } else if dispute.nays.len() >= threshold {
+                let submission =
+                    Self::submissions(id, submission_id).ok_or(Error::<T>::UnknownSubmission)?;
+                let (slashed, _) = T::Currency::slash_reserved(&submission.submitter, dispute.bond);
+                T::Slashed::on_unbalanced(slashed);
+                Submissions::<T>::remove(id, submission_id);
+                Disputes::<T>::remove(id, submission_id);
+                Self::deposit_event(RawEvent::DisputeResolved(id, submission_id, false));
+            } else {
+                Disputes::<T>::insert(id, submission_id, dispute);
+            }
+            Ok(())
+        }
+
+        /// Hands the approval of submissions over to `uids`, of which
+        /// `threshold` have to approve a submission to pay it out. The
+        /// approvers of a bounty can't be changed.
+        #[weight = T::WeightInfo::set_approvers(uids.len() as u32)]
+        fn set_approvers(
+            origin,
+            id: T::EscrowId,
+            uids: Vec<T::Uid>,
+            threshold: u32,
+        ) -> DispatchResult {
+            let poster = ensure_signed(origin)?;
+            let mut uids = uids;
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            ensure!(bounty.poster == poster, Error::<T>::NotPoster);
+            ensure!(!Approvers::<T>::contains_key(id), Error::<T>::ApproversAlreadySet);
+            uids.sort();
+            uids.dedup();
+            ensure!(
+                uids.len() as u32 <= T::MaxApprovers::get(),
+                Error::<T>::TooManyApprovers
+            );
+            ensure!(
+                threshold > 0 && threshold as usize <= uids.len(),
+                Error::<T>::InvalidThreshold
+            );
+
+            Approvers::<T>::insert(id, ApproverSet {
+                uids: uids.clone(),
+                threshold,
+            });
+            Self::deposit_event(RawEvent::ApproversSet(id, threshold, uids));
+            Ok(())
+        }
+
+        /// Makes the payouts of a bounty vest linearly over `period` blocks.
+        /// The vesting period of a bounty can't be changed.
+        #[weight = T::WeightInfo::set_vesting()]
+        fn set_vesting(origin, id: T::EscrowId, period: T::BlockNumber) -> DispatchResult {
+            let poster = ensure_signed(origin)?;
+            let bounty = Self::bounties(id).ok_or(Error::<T>::UnknownBounty)?;
+            ensure!(bounty.poster == poster, Error::<T>::NotPoster);
+            ensure!(!VestingPeriods::<T>::contains_key(id), Error::<T>::VestingAlreadySet);
+            ensure!(
+                !period.is_zero() && period <= T::MaxVestingPeriod::get(),
+                Error::<T>::InvalidVestingPeriod
+            );
+
+            VestingPeriods::<T>::insert(id, period);
+            Self::deposit_event(RawEvent::VestingSet(id, period));
+            Ok(())
+        }
+
+        /// Unlocks the funds of the signer that vested so far.
+        #[weight = T::WeightInfo::vest(T::MaxVestingSchedules::get())]
+        fn vest(origin) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+            let locked = Self::update_vesting(&who, vesting.schedules);
+            Self::deposit_event(RawEvent::Vested(who, locked));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Account holding the funds of bounty `id`.
+    pub fn account_id(id: T::EscrowId) -> T::AccountId {
+        T::Foundation::get().into_sub_account((b"escrow", id))
+    }
+
+    /// Open bounties with at least `min_total` contributed, ordered by the
+    /// total contributed from highest to lowest. Returns `limit` bounties
+    /// starting at `offset`.
+    pub fn open_bounties(
+        min_total: BalanceOf<T>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(T::EscrowId, EscrowBountyOf<T>)> {
+        let mut bounties = Bounties::<T>::iter()
+            .filter(|(_, bounty)| bounty.total >= min_total)
+            .collect::<Vec<_>>();
+        bounties.sort_by(|(a_id, a), (b_id, b)| b.total.cmp(&a.total).then(a_id.cmp(b_id)));
+        bounties
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Submissions for bounty `id`.
+    pub fn bounty_submissions(
+        id: T::EscrowId,
+    ) -> Vec<(
+        T::SubmissionId,
+        MilestoneSubmission<T::AccountId, T::IpfsReference>,
+    )> {
+        Submissions::<T>::iter_prefix(id).collect()
+    }
+
+    /// Submissions for the next milestone of team bounties that `uid` can
+    /// approve but didn't yet, with the uids that already approved them.
+    pub fn pending_approvals(uid: T::Uid) -> Vec<(T::EscrowId, T::SubmissionId, Vec<T::Uid>)> {
+        Approvers::<T>::iter()
+            .filter(|(_, approvers)| approvers.uids.binary_search(&uid).is_ok())
+            .filter_map(|(id, _)| Some((id, Self::bounties(id)?)))
+            .flat_map(|(id, bounty)| {
+                Submissions::<T>::iter_prefix(id)
+                    .filter(move |(_, submission)| {
+                        !submission.rejected && submission.milestone == bounty.next_milestone
+                    })
+                    .map(move |(submission_id, _)| {
+                        (id, submission_id, Self::approvals(id, submission_id))
+                    })
+            })
+            .filter(|(_, _, approvals)| !approvals.contains(&uid))
+            .collect()
+    }
+
+    /// Free balance of `account` and how much of it is locked by vesting
+    /// payouts.
+    pub fn vesting_balance(account: &T::AccountId) -> VestingBalance<BalanceOf<T>> {
+        let free = T::Currency::free_balance(account);
+        let (locked, vested) = match Self::vesting(account) {
+            Some(vesting) => {
+                let now = <frame_system::Module<T>>::block_number();
+                let locked = vesting
+                    .schedules
+                    .iter()
+                    .fold(BalanceOf::<T>::zero(), |acc, schedule| {
+                        acc.saturating_add(schedule.locked_at(now))
+                    });
+                (locked, vesting.locked.saturating_sub(locked))
+            }
+            None => (Zero::zero(), Zero::zero()),
+        };
+        VestingBalance {
+            free,
+            locked,
+            vested,
+        }
+    }
+
+    /// Contributions of `account` to open bounties.
+    pub fn account_contributions(account: &T::AccountId) -> Vec<(T::EscrowId, BalanceOf<T>)> {
+        Contributions::<T>::iter()
+            .filter(|(_, contributor, _)| contributor == account)
+            .map(|(id, _, amount)| (id, amount))
+            .collect()
+    }
+
+    /// Uid of `account` if it is one of `approvers`.
+    fn approver_uid(
+        approvers: &ApproverSetOf<T>,
+        account: &T::AccountId,
+    ) -> Result<T::Uid, Error<T>> {
+        T::UidLookup::uid(account)
+            .filter(|uid| approvers.uids.binary_search(uid).is_ok())
+            .ok_or(Error::<T>::NotApprover)
+    }
+
+    /// Pays out the milestone of a submission.
+    ///
+    /// Has to be called from a transactional dispatch, since the payout is
+    /// transferred before the vesting schedule is started.
+    fn pay_milestone(
+        id: T::EscrowId,
+        mut bounty: EscrowBountyOf<T>,
+        submission_id: T::SubmissionId,
+    ) -> DispatchResult {
+        let submission =
+            Self::submissions(id, submission_id).ok_or(Error::<T>::UnknownSubmission)?;
+        // Rejected submissions are only paid out when the arbiters resolve a
+        // dispute in favour of the submitter.
+        ensure!(
+            !submission.rejected || Disputes::<T>::contains_key(id, submission_id),
+            Error::<T>::SubmissionRejected
+        );
+        ensure!(
+            submission.milestone == bounty.next_milestone,
+            Error::<T>::MilestoneOutOfOrder
+        );
+        let amount = bounty.milestones[submission.milestone as usize];
+        let account = Self::account_id(id);
+        bounty.next_milestone += 1;
+        let completed = bounty.next_milestone as usize == bounty.milestones.len();
+
+        // The escrow account is only reaped once the last milestone is paid.
+        // Since the last milestone is at least the existential deposit, a
+        // fully funded bounty always keeps it until then.
+        let (required, existence) = if completed {
+            (amount, ExistenceRequirement::AllowDeath)
+        } else {
+            (
+                amount.saturating_add(T::Currency::minimum_balance()),
+                ExistenceRequirement::KeepAlive,
+            )
+        };
+        ensure!(
+            T::Currency::free_balance(&account) >= required,
+            Error::<T>::InsufficientFunds
+        );
+        T::Currency::transfer(&account, &submission.submitter, amount, existence)?;
+        if let Some(period) = Self::vesting_period(id) {
+            Self::start_vesting(&submission.submitter, amount, period)?;
+        }
+        Submissions::<T>::remove(id, submission_id);
+        Approvals::<T>::remove(id, submission_id);
+        if let Some(dispute) = Disputes::<T>::take(id, submission_id) {
+            T::Currency::unreserve(&submission.submitter, dispute.bond);
+        }
+        Self::deposit_event(RawEvent::MilestonePaid(
+            id,
+            submission_id,
+            submission.milestone,
+            submission.submitter,
+            amount,
+        ));
+
+        if completed {
+            Bounties::<T>::remove(id);
+            Contributions::<T>::remove_prefix(id);
+            Self::release_disputes(id);
+            Submissions::<T>::remove_prefix(id);
+            Approvers::<T>::remove(id);
+            Approvals::<T>::remove_prefix(id);
+            VestingPeriods::<T>::remove(id);
+            Expirations::<T>::mutate(bounty.deadline, |ids| ids.retain(|other| *other != id));
+            Self::deposit_event(RawEvent::EscrowCompleted(id));
+        } else {
+            Bounties::<T>::insert(id, bounty);
+        }
+        Ok(())
+    }
+
+    /// Closes bounty `id` and refunds the escrow to the contributors pro rata.
+    ///
+    /// The last contributor also receives the rounding dust, which reaps the
+    /// escrow account. Refunds that can't be transferred, like a refund below
+    /// the existential deposit to a reaped account, are handed to `Slashed`
+    /// instead of staying in the escrow account.
+    fn expire(id: T::EscrowId) -> Weight {
+        if Bounties::<T>::take(id).is_none() {
+            return T::DbWeight::get().reads_writes(1, 1);
+        }
+        let disputes = Self::release_disputes(id);
+        let submissions = Submissions::<T>::drain_prefix(id).count();
+        Approvers::<T>::remove(id);
+        Approvals::<T>::remove_prefix(id);
+        VestingPeriods::<T>::remove(id);
+        let contributions = Contributions::<T>::drain_prefix(id).collect::<Vec<_>>();
+        let contributed = contributions
+            .iter()
+            .fold(BalanceOf::<T>::zero(), |acc, (_, amount)| {
+                acc.saturating_add(*amount)
+            });
+        let account = Self::account_id(id);
+        let available = T::Currency::free_balance(&account);
+        let mut remaining = available;
+        let mut failed = BalanceOf::<T>::zero();
+        let count = contributions.len();
+        for (i, (contributor, amount)) in contributions.into_iter().enumerate() {
+            let refund = if i + 1 == count {
+                remaining.saturating_sub(failed)
+            } else {
+                (Perbill::from_rational_approximation(amount, contributed) * available)
+                    .min(remaining)
+            };
+            if refund.is_zero() {
+                continue;
+            }
+            // Keeping the escrow account alive would fail the refund of a
+            // contributor after another refund failed. The account is only
+            // reaped once less than the existential deposit is left.
+            if T::Currency::transfer(
+                &account,
+                &contributor,
+                refund,
+                ExistenceRequirement::AllowDeath,
+            )
+            .is_err()
+            {
+                failed = failed.saturating_add(refund);
+                continue;
+            }
+            remaining -= refund;
+            Self::deposit_event(RawEvent::EscrowRefunded(id, contributor, refund));
+        }
+        let unrefunded = T::Currency::free_balance(&account);
+        if !unrefunded.is_zero() {
+            let (slashed, _) = T::Currency::slash(&account, unrefunded);
+            T::Slashed::on_unbalanced(slashed);
+        }
+        Self::deposit_event(RawEvent::EscrowExpired(id));
+
+        T::WeightInfo::expire(count as u32, disputes as u32, submissions as u32)
+    }
+
+    /// Locks `amount` of the funds of `who` and unlocks it linearly over
+    /// `period` blocks.
+    fn start_vesting(
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+        period: T::BlockNumber,
+    ) -> DispatchResult {
+        let now = <frame_system::Module<T>>::block_number();
+        let mut schedules = Self::vesting(who)
+            .map(|vesting| vesting.schedules)
+            .unwrap_or_default();
+        schedules.retain(|schedule| !schedule.locked_at(now).is_zero());
+        ensure!(
+            (schedules.len() as u32) < T::MaxVestingSchedules::get(),
+            Error::<T>::TooManyVestingSchedules
+        );
+        schedules.push(VestingSchedule {
+            locked: amount,
+            start: now,
+            period,
+        });
+        Self::update_vesting(who, schedules);
+        Self::deposit_event(RawEvent::VestingStarted(who.clone(), amount, period));
+        Ok(())
+    }
+
+    /// Drops the schedules of `who` that fully vested and locks the amount
+    /// that didn't vest yet. Returns the locked amount.
+    fn update_vesting(
+        who: &T::AccountId,
+        mut schedules: Vec<VestingSchedule<BalanceOf<T>, T::BlockNumber>>,
+    ) -> BalanceOf<T> {
+        let now = <frame_system::Module<T>>::block_number();
+        schedules.retain(|schedule| !schedule.locked_at(now).is_zero());
+        let locked = schedules
+            .iter()
+            .fold(BalanceOf::<T>::zero(), |acc, schedule| {
+                acc.saturating_add(schedule.locked_at(now))
+            });
+        if schedules.is_empty() {
+            T::Currency::remove_lock(VESTING_ID, who);
+            VestingSchedules::<T>::remove(who);
+        } else {
+            // Like `pallet_vesting`, the lock doesn't stop the account from
+            // paying transaction fees.
+            let reasons = WithdrawReasons::all() ^ WithdrawReason::TransactionPayment;
+            T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+            VestingSchedules::<T>::insert(who, Vesting { schedules, locked });
+        }
+        locked
+    }
+
+    /// Removes the open disputes of bounty `id` and returns their bonds.
+    ///
+    /// Returns the number of disputes that were released.
+    fn release_disputes(id: T::EscrowId) -> usize {
+        let mut count = 0;
+        for (submission_id, dispute) in Disputes::<T>::drain_prefix(id) {
+            if let Some(submission) = Self::submissions(id, submission_id) {
+                T::Currency::unreserve(&submission.submitter, dispute.bond);
+            }
+            count += 1;
+        }
+        count
+    }
+}
@@ -0,0 +1,183 @@
+//! Test runtime of the escrow pallet.
+use crate as escrow;
+use frame_support::traits::{Contains, OnInitialize};
+use frame_support::weights::Weight;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::testing::Header;
+use sp_runtime::traits::{BlakeTwo256, IdentityLookup};
+use sp_runtime::{ModuleId, Perbill};
+
+impl_outer_origin! {
+    pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+    pub enum TestEvent for Test {
+        system<T>,
+        pallet_balances<T>,
+        escrow<T>,
+    }
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const MaximumBlockWeight: Weight = 1024;
+    pub const MaximumBlockLength: u32 = 2 * 1024;
+    pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+    type BaseCallFilter = ();
+    type Origin = Origin;
+    type Call = ();
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = TestEvent;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type DbWeight = ();
+    type BlockExecutionWeight = ();
+    type ExtrinsicBaseWeight = ();
+    type MaximumExtrinsicWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type PalletInfo = ();
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 10;
+    pub const MaxLocks: u32 = 10;
+}
+
+impl pallet_balances::Trait for Test {
+    type Balance = u64;
+    type Event = TestEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const Foundation: ModuleId = ModuleId(*b"fundacon");
+    pub const MinDeposit: u64 = 10;
+    pub const MinContribution: u64 = 5;
+    pub const MaxMilestones: u32 = 4;
+    pub const MinLifetime: u64 = 10;
+    pub const MaxLifetime: u64 = 100;
+    pub const MaxContributors: u32 = 3;
+    pub const MaxDisputes: u32 = 2;
+    pub const MaxSubmissions: u32 = 3;
+    pub const MaxExpirations: u32 = 2;
+    pub const DisputeBond: u64 = 20;
+    pub const MaxApprovers: u32 = 4;
+    pub const MaxVestingPeriod: u64 = 100;
+    pub const MaxVestingSchedules: u32 = 2;
+}
+
+/// Every account is the device of the uid with the same number.
+pub struct AccountUid;
+
+impl escrow::UidLookup<u64, u64> for AccountUid {
+    fn uid(account: &u64) -> Option<u64> {
+        Some(*account)
+    }
+}
+
+/// Accounts 10, 11 and 12 are arbiters.
+pub struct Arbiters;
+
+impl Contains<u64> for Arbiters {
+    fn sorted_members() -> Vec<u64> {
+        vec![10, 11, 12]
+    }
+}
+
+impl escrow::Trait for Test {
+    type Event = TestEvent;
+    type IpfsReference = u64;
+    type Currency = Balances;
+    type EscrowId = u64;
+    type SubmissionId = u64;
+    type Uid = u64;
+    type UidLookup = AccountUid;
+    type MaxApprovers = MaxApprovers;
+    type MaxVestingPeriod = MaxVestingPeriod;
+    type MaxVestingSchedules = MaxVestingSchedules;
+    type Foundation = Foundation;
+    type MinDeposit = MinDeposit;
+    type MinContribution = MinContribution;
+    type MaxMilestones = MaxMilestones;
+    type MinLifetime = MinLifetime;
+    type MaxLifetime = MaxLifetime;
+    type MaxContributors = MaxContributors;
+    type MaxDisputes = MaxDisputes;
+    type MaxSubmissions = MaxSubmissions;
+    type MaxExpirations = MaxExpirations;
+    type DisputeBond = DisputeBond;
+    type Arbiters = Arbiters;
+    type Slashed = ();
+    type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Escrow = escrow::Module<Test>;
+
+/// Accounts 1 to 4 and the arbiters start with 1000 units each.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (1, 1000),
+            (2, 1000),
+            (3, 1000),
+            (4, 1000),
+            (10, 1000),
+            (11, 1000),
+            (12, 1000),
+        ],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Initializes the blocks up to `n`.
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Escrow::on_initialize(System::block_number());
+    }
+}
+
+/// Escrow events deposited so far.
+pub fn escrow_events() -> Vec<escrow::Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            TestEvent::escrow(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,419 @@
+use crate::mock::*;
+use crate::{BountyNonce, Error, RawEvent, SubmissionNonce, Submissions};
+use frame_support::storage::{IterableStorageDoubleMap, StorageValue};
+use frame_support::{assert_noop, assert_ok};
+use pallet_balances::Reasons;
+
+/// Posts a bounty of account 1 with two milestones of 50 and funds it.
+fn post() -> u64 {
+    let id = BountyNonce::<Test>::get();
+    assert_ok!(Escrow::post_bounty(
+        Origin::signed(1),
+        0,
+        vec![50, 50],
+        100,
+        20
+    ));
+    id
+}
+
+/// Submits work of account 2 for `milestone` of bounty `id`.
+fn submit(id: u64, milestone: u32) -> u64 {
+    let submission_id = SubmissionNonce::<Test>::get();
+    assert_ok!(Escrow::submit(Origin::signed(2), id, milestone, 0));
+    submission_id
+}
+
+#[test]
+fn approve_pays_out_milestone() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::approve(Origin::signed(1), id, submission_id));
+        assert_eq!(Balances::free_balance(2), 1050);
+        assert_eq!(Balances::free_balance(Escrow::account_id(id)), 50);
+        assert_eq!(Escrow::bounties(id).unwrap().next_milestone, 1);
+        assert!(Escrow::submissions(id, submission_id).is_none());
+    });
+}
+
+#[test]
+fn approve_only_by_poster() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        let submission_id = submit(id, 0);
+        assert_noop!(
+            Escrow::approve(Origin::signed(3), id, submission_id),
+            Error::<Test>::NotPoster
+        );
+    });
+}
+
+#[test]
+fn failed_vesting_reverts_payout() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        assert_ok!(Escrow::set_vesting(Origin::signed(1), id, 10));
+        for _ in 0..MaxVestingSchedules::get() {
+            Escrow::start_vesting(&2, 10, 10).unwrap();
+        }
+        let submission_id = submit(id, 0);
+        let escrow = Balances::free_balance(Escrow::account_id(id));
+        assert_noop!(
+            Escrow::approve(Origin::signed(1), id, submission_id),
+            Error::<Test>::TooManyVestingSchedules
+        );
+        assert_eq!(Balances::free_balance(Escrow::account_id(id)), escrow);
+        assert_eq!(Balances::free_balance(2), 1000);
+        assert!(Escrow::submissions(id, submission_id).is_some());
+    });
+}
+
+#[test]
+fn vesting_payout_is_locked() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        assert_ok!(Escrow::set_vesting(Origin::signed(1), id, 10));
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::approve(Origin::signed(1), id, submission_id));
+        let locks = Balances::locks(2);
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].amount, 50);
+        // The submitter can still pay transaction fees.
+        assert_eq!(locks[0].reasons, Reasons::Misc);
+
+        run_to_block(6);
+        assert_ok!(Escrow::vest(Origin::signed(2)));
+        assert_eq!(Balances::locks(2)[0].amount, 25);
+        assert!(escrow_events().contains(&RawEvent::Vested(2, 25)));
+
+        run_to_block(11);
+        assert_ok!(Escrow::vest(Origin::signed(2)));
+        assert!(Balances::locks(2).is_empty());
+        assert!(Escrow::vesting(2).is_none());
+    });
+}
+
+#[test]
+fn last_milestone_covers_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Escrow::post_bounty(Origin::signed(1), 0, vec![50, 5], 50, 20),
+            Error::<Test>::LastMilestoneTooLow
+        );
+    });
+}
+
+#[test]
+fn payout_keeps_existential_deposit_in_escrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Escrow::post_bounty(
+            Origin::signed(1),
+            0,
+            vec![50, 50],
+            55,
+            20
+        ));
+        let submission_id = submit(0, 0);
+        assert_noop!(
+            Escrow::approve(Origin::signed(1), 0, submission_id),
+            Error::<Test>::InsufficientFunds
+        );
+
+        assert_ok!(Escrow::contribute(Origin::signed(3), 0, 5));
+        assert_ok!(Escrow::approve(Origin::signed(1), 0, submission_id));
+        assert_eq!(Balances::free_balance(2), 1050);
+        assert_eq!(
+            Balances::free_balance(Escrow::account_id(0)),
+            ExistentialDeposit::get()
+        );
+    });
+}
+
+#[test]
+fn rejected_submission_is_not_paid() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::reject(Origin::signed(1), id, submission_id));
+        assert_noop!(
+            Escrow::approve(Origin::signed(1), id, submission_id),
+            Error::<Test>::SubmissionRejected
+        );
+    });
+}
+
+#[test]
+fn rejected_team_submission_is_not_approved() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        assert_ok!(Escrow::set_approvers(Origin::signed(1), id, vec![3, 4], 2));
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::reject(Origin::signed(3), id, submission_id));
+        assert_noop!(
+            Escrow::approve(Origin::signed(4), id, submission_id),
+            Error::<Test>::SubmissionRejected
+        );
+    });
+}
+
+#[test]
+fn team_approval_pays_out_at_threshold() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        assert_ok!(Escrow::set_approvers(Origin::signed(1), id, vec![3, 4], 2));
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::approve(Origin::signed(3), id, submission_id));
+        assert_eq!(Escrow::approvals(id, submission_id), vec![3]);
+        assert_eq!(Balances::free_balance(2), 1000);
+
+        assert_ok!(Escrow::approve(Origin::signed(4), id, submission_id));
+        assert_eq!(Balances::free_balance(2), 1050);
+        let events = escrow_events();
+        assert_eq!(
+            &events[events.len() - 2..],
+            &[
+                RawEvent::MilestonePaid(id, submission_id, 0, 2, 50),
+                RawEvent::MilestoneApproved(id, submission_id, 4, 2),
+            ]
+        );
+    });
+}
+
+#[test]
+fn failed_team_payout_emits_no_approval() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        assert_ok!(Escrow::set_approvers(Origin::signed(1), id, vec![3], 1));
+        assert_ok!(Escrow::set_vesting(Origin::signed(1), id, 10));
+        for _ in 0..MaxVestingSchedules::get() {
+            Escrow::start_vesting(&2, 10, 10).unwrap();
+        }
+        let submission_id = submit(id, 0);
+        assert_noop!(
+            Escrow::approve(Origin::signed(3), id, submission_id),
+            Error::<Test>::TooManyVestingSchedules
+        );
+        assert!(!escrow_events()
+            .iter()
+            .any(|event| matches!(event, RawEvent::MilestoneApproved(..))));
+    });
+}
+
+#[test]
+fn dispute_pays_out_rejected_submission() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::reject(Origin::signed(1), id, submission_id));
+        assert_ok!(Escrow::open_dispute(
+            Origin::signed(2),
+            id,
+            submission_id,
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(2), DisputeBond::get());
+        assert_ok!(Escrow::vote_dispute(
+            Origin::signed(10),
+            id,
+            submission_id,
+            true
+        ));
+        assert_ok!(Escrow::vote_dispute(
+            Origin::signed(11),
+            id,
+            submission_id,
+            true
+        ));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 1050);
+        assert!(escrow_events().contains(&RawEvent::DisputeResolved(id, submission_id, true)));
+    });
+}
+
+#[test]
+fn dispute_of_paid_milestone_is_dismissed() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        let submission_id = submit(id, 0);
+        assert_ok!(Escrow::reject(Origin::signed(1), id, submission_id));
+        assert_ok!(Escrow::open_dispute(
+            Origin::signed(2),
+            id,
+            submission_id,
+            0
+        ));
+        let other_id = SubmissionNonce::<Test>::get();
+        assert_ok!(Escrow::submit(Origin::signed(3), id, 0, 0));
+        assert_ok!(Escrow::approve(Origin::signed(1), id, other_id));
+
+        assert_ok!(Escrow::vote_dispute(
+            Origin::signed(10),
+            id,
+            submission_id,
+            true
+        ));
+        assert_ok!(Escrow::vote_dispute(
+            Origin::signed(11),
+            id,
+            submission_id,
+            true
+        ));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 1000);
+        assert_eq!(Balances::free_balance(Escrow::account_id(id)), 50);
+        assert!(Escrow::disputes(id, submission_id).is_none());
+        assert!(escrow_events().contains(&RawEvent::DisputeDismissed(id, submission_id)));
+    });
+}
+
+#[test]
+fn expire_refunds_contributors() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Escrow::post_bounty(
+            Origin::signed(1),
+            0,
+            vec![50, 50],
+            60,
+            20
+        ));
+        assert_ok!(Escrow::contribute(Origin::signed(3), 0, 40));
+        let submission_id = submit(0, 0);
+        assert_ok!(Escrow::approve(Origin::signed(1), 0, submission_id));
+
+        run_to_block(21);
+        assert!(Escrow::bounties(0).is_none());
+        assert_eq!(Balances::free_balance(1), 970);
+        assert_eq!(Balances::free_balance(3), 980);
+        assert_eq!(Balances::free_balance(Escrow::account_id(0)), 0);
+        assert!(escrow_events().contains(&RawEvent::EscrowExpired(0)));
+    });
+}
+
+#[test]
+fn failed_refund_is_not_paid_to_others() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Escrow::post_bounty(
+            Origin::signed(1),
+            0,
+            vec![50, 50],
+            90,
+            20
+        ));
+        assert_ok!(Escrow::contribute(Origin::signed(3), 0, 10));
+        let submission_id = submit(0, 0);
+        assert_ok!(Escrow::approve(Origin::signed(1), 0, submission_id));
+        // The refund of 5 can't recreate the reaped account of 3.
+        assert_ok!(Balances::transfer(Origin::signed(3), 4, 990));
+
+        run_to_block(21);
+        assert_eq!(Balances::free_balance(1), 955);
+        assert_eq!(Balances::free_balance(3), 0);
+        assert!(!escrow_events()
+            .iter()
+            .any(|event| matches!(event, RawEvent::EscrowRefunded(_, 3, _))));
+    });
+}
+
+#[test]
+fn failed_refunds_are_slashed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Escrow::post_bounty(
+            Origin::signed(1),
+            0,
+            vec![50, 50],
+            80,
+            20
+        ));
+        assert_ok!(Escrow::contribute(Origin::signed(3), 0, 10));
+        assert_ok!(Escrow::contribute(Origin::signed(4), 0, 10));
+        let submission_id = submit(0, 0);
+        assert_ok!(Escrow::approve(Origin::signed(1), 0, submission_id));
+        // Together the refunds of 5 to the reaped accounts of 3 and 4 reach
+        // the existential deposit of the escrow account.
+        assert_ok!(Balances::transfer(Origin::signed(3), 2, 990));
+        assert_ok!(Balances::transfer(Origin::signed(4), 2, 990));
+        let issuance = Balances::total_issuance();
+
+        run_to_block(21);
+        assert_eq!(Balances::free_balance(1), 960);
+        assert_eq!(Balances::free_balance(3), 0);
+        assert_eq!(Balances::free_balance(4), 0);
+        assert_eq!(Balances::free_balance(Escrow::account_id(0)), 0);
+        assert_eq!(Balances::total_issuance(), issuance - 10);
+    });
+}
+
+#[test]
+fn expirations_are_capped_per_block() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..3 {
+            post();
+        }
+        assert_eq!(Escrow::expirations(21), vec![0, 1, 2]);
+
+        run_to_block(21);
+        assert!(Escrow::bounties(0).is_none());
+        assert!(Escrow::bounties(1).is_none());
+        assert!(Escrow::bounties(2).is_some());
+        assert_eq!(Escrow::expirations(22), vec![2]);
+
+        run_to_block(22);
+        assert!(Escrow::bounties(2).is_none());
+        assert!(Escrow::expirations(22).is_empty());
+    });
+}
+
+#[test]
+fn contributors_are_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Escrow::post_bounty(
+            Origin::signed(1),
+            0,
+            vec![50, 50],
+            10,
+            20
+        ));
+        assert_ok!(Escrow::contribute(Origin::signed(2), 0, 10));
+        assert_ok!(Escrow::contribute(Origin::signed(3), 0, 10));
+        assert_noop!(
+            Escrow::contribute(Origin::signed(4), 0, 10),
+            Error::<Test>::TooManyContributors
+        );
+        assert_ok!(Escrow::contribute(Origin::signed(3), 0, 10));
+    });
+}
+
+#[test]
+fn disputes_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        for submitter in 2..=4 {
+            let submission_id = SubmissionNonce::<Test>::get();
+            assert_ok!(Escrow::submit(Origin::signed(submitter), id, 0, 0));
+            let dispute = Escrow::open_dispute(Origin::signed(submitter), id, submission_id, 0);
+            if submitter < 4 {
+                assert_ok!(dispute);
+            } else {
+                assert_eq!(dispute, Err(Error::<Test>::TooManyDisputes.into()));
+            }
+        }
+    });
+}
+
+#[test]
+fn submissions_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let id = post();
+        for _ in 0..MaxSubmissions::get() {
+            submit(id, 0);
+        }
+        assert_noop!(
+            Escrow::submit(Origin::signed(3), id, 0, 0),
+            Error::<Test>::TooManySubmissions
+        );
+
+        run_to_block(21);
+        assert_eq!(Submissions::<Test>::iter_prefix(id).count(), 0);
+    });
+}
@@ -0,0 +1,105 @@
+//! Weights for the escrow pallet.
+//!
+//! The weights of `()` are estimates. Runtimes plug in the weights generated
+//! with `sunshine-node benchmark --pallet sunshine_escrow_pallet`.
+#![allow(unused_parens)]
+
+use frame_support::traits::Get;
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+pub trait WeightInfo {
+    fn post_bounty(m: u32) -> Weight;
+    fn contribute(c: u32) -> Weight;
+    fn submit(s: u32) -> Weight;
+    fn approve() -> Weight;
+    fn approve_team(a: u32) -> Weight;
+    fn reject() -> Weight;
+    fn open_dispute(d: u32) -> Weight;
+    fn vote_dispute() -> Weight;
+    fn expire(c: u32, d: u32, s: u32) -> Weight;
+    fn set_approvers(a: u32) -> Weight;
+    fn set_vesting() -> Weight;
+    fn vest(s: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+    fn post_bounty(m: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((100_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+    }
+    fn contribute(c: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn submit(s: u32) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn approve() -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().writes(7 as Weight))
+    }
+    fn approve_team(a: u32) -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn reject() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(5 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn open_dispute(d: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(d as Weight))
+            .saturating_add(DbWeight::get().reads(4 as Weight))
+            .saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn vote_dispute() -> Weight {
+        (200_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn expire(c: u32, d: u32, s: u32) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((50_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add((20_000_000 as Weight).saturating_mul(d as Weight))
+            .saturating_add((10_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(s as Weight)))
+    }
+    fn set_approvers(a: u32) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((100_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn set_vesting() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn vest(s: u32) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+}
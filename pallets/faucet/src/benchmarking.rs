@@ -0,0 +1,27 @@
+//! Benchmarks for the faucet pallet.
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+benchmarks! {
+    _ { }
+
+    // The first mint of a period starts a new period and creates the account.
+    mint {
+        let account: T::AccountId = account("account", 0, SEED);
+        let next_mint = Module::<T>::next_mint(&account);
+        let nonce = (0u64 ..)
+            .find(|nonce| {
+                leading_zeros(&pow_hash(&account, next_mint, *nonce)) >= T::Difficulty::get()
+            })
+            .ok_or("no proof of work")?;
+    }: _(RawOrigin::None, account.clone(), nonce)
+    verify {
+        assert_eq!(Module::<T>::period_mints(), 1);
+        assert_eq!(Module::<T>::next_mint(&account), next_mint + T::Cooldown::get());
+    }
+}
@@ -0,0 +1,181 @@
+//! Faucet minting funds for new accounts with unsigned transactions.
+//!
+//! Unsigned transactions don't pay fees, so minting is rate limited. An
+//! account can mint again `Cooldown` blocks after its last mint and at most
+//! `MintsPerPeriod` mints are accepted every `MintPeriod` blocks across all
+//! accounts. A mint can be required to carry a proof of work, a nonce for
+//! which the hash of the account, the block it may mint at and the nonce
+//! starts with `Difficulty` zero bits. Proofs can't be reused, since the
+//! block an account may mint at changes with every mint.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::traits::{Currency, Get, Imbalance};
+use frame_support::{decl_error, decl_event, decl_module, decl_storage};
+use frame_system::ensure_none;
+use parity_scale_codec::Encode;
+use sp_runtime::traits::{Saturating, Zero};
+use sp_runtime::transaction_validity::{
+    InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
+};
+use sp_runtime::DispatchResult;
+use sp_std::prelude::*;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod weights;
+
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: frame_system::Trait {
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// The currency minted by the faucet.
+    type Currency: Currency<Self::AccountId>;
+    /// Amount minted per mint.
+    type MintUnit: Get<BalanceOf<Self>>;
+    /// Number of blocks an account has to wait between mints.
+    type Cooldown: Get<Self::BlockNumber>;
+    /// Number of blocks the global mint limit applies to.
+    type MintPeriod: Get<Self::BlockNumber>;
+    /// Maximum number of mints per `MintPeriod`.
+    type MintsPerPeriod: Get<u32>;
+    /// Number of leading zero bits of the proof of work, zero disables it.
+    type Difficulty: Get<u32>;
+    /// Weights of the dispatchables.
+    type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Faucet {
+        /// First block an account may mint at.
+        pub NextMint get(fn next_mint): map
+            hasher(blake2_128_concat) T::AccountId => T::BlockNumber;
+        /// First block of the current mint period.
+        pub PeriodStart get(fn period_start): T::BlockNumber;
+        /// Number of mints in the current mint period.
+        pub PeriodMints get(fn period_mints): u32;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        <T as frame_system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        /// Funds were minted. \[account, amount\]
+        Minted(AccountId, Balance),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// The account minted less than `Cooldown` blocks ago.
+        Cooldown,
+        /// `MintsPerPeriod` was reached in the current mint period.
+        PeriodLimit,
+        /// The proof of work doesn't meet the `Difficulty`.
+        InvalidProof,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        const MintUnit: BalanceOf<T> = T::MintUnit::get();
+        const Cooldown: T::BlockNumber = T::Cooldown::get();
+        const MintPeriod: T::BlockNumber = T::MintPeriod::get();
+        const MintsPerPeriod: u32 = T::MintsPerPeriod::get();
+        const Difficulty: u32 = T::Difficulty::get();
+
+        /// Mints `MintUnit` to `account`. `nonce` is the proof of work.
+        #[weight = T::WeightInfo::mint()]
+        fn mint(origin, account: T::AccountId, nonce: u64) -> DispatchResult {
+            ensure_none(origin)?;
+            Self::check_mint(&account, nonce)?;
+
+            let now = <frame_system::Module<T>>::block_number();
+            if Self::period_ended(now) {
+                PeriodStart::<T>::put(now);
+                PeriodMints::put(1);
+            } else {
+                PeriodMints::mutate(|mints| *mints += 1);
+            }
+            NextMint::<T>::insert(&account, now.saturating_add(T::Cooldown::get()));
+            let amount = T::Currency::deposit_creating(&account, T::MintUnit::get()).peek();
+            Self::deposit_event(RawEvent::Minted(account, amount));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Checks the rate limits and the proof of work of a mint.
+    pub fn check_mint(account: &T::AccountId, nonce: u64) -> Result<(), Error<T>> {
+        let now = <frame_system::Module<T>>::block_number();
+        let next_mint = Self::next_mint(account);
+        if now < next_mint {
+            return Err(Error::<T>::Cooldown);
+        }
+        if !Self::period_ended(now) && Self::period_mints() >= T::MintsPerPeriod::get() {
+            return Err(Error::<T>::PeriodLimit);
+        }
+        if leading_zeros(&pow_hash(account, next_mint, nonce)) < T::Difficulty::get() {
+            return Err(Error::<T>::InvalidProof);
+        }
+        Ok(())
+    }
+
+    fn period_ended(now: T::BlockNumber) -> bool {
+        now >= Self::period_start().saturating_add(T::MintPeriod::get())
+            || Self::period_mints().is_zero()
+    }
+}
+
+/// Hash the proof of work of a mint is checked against.
+pub fn pow_hash<AccountId: Encode, BlockNumber: Encode>(
+    account: &AccountId,
+    next_mint: BlockNumber,
+    nonce: u64,
+) -> [u8; 32] {
+    sp_io::hashing::blake2_256(&(account, next_mint, nonce).encode())
+}
+
+/// Number of leading zero bits of a hash.
+pub fn leading_zeros(hash: &[u8; 32]) -> u32 {
+    let mut zeros = 0;
+    for byte in hash.iter() {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
+}
+
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+        if let Call::mint(account, nonce) = call {
+            Self::check_mint(account, *nonce)
+                .map_err(|err| InvalidTransaction::Custom(err.as_u8()))?;
+            ValidTransaction::with_tag_prefix("Faucet")
+                .and_provides(account)
+                .longevity(64)
+                .propagate(true)
+                .build()
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
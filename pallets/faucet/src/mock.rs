@@ -0,0 +1,117 @@
+//! Test runtime of the faucet pallet.
+use crate as faucet;
+use frame_support::weights::Weight;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::testing::Header;
+use sp_runtime::traits::{BlakeTwo256, IdentityLookup};
+use sp_runtime::Perbill;
+
+impl_outer_origin! {
+    pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+    pub enum TestEvent for Test {
+        system<T>,
+        pallet_balances<T>,
+        faucet<T>,
+    }
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const MaximumBlockWeight: Weight = 1024;
+    pub const MaximumBlockLength: u32 = 2 * 1024;
+    pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+    type BaseCallFilter = ();
+    type Origin = Origin;
+    type Call = ();
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = TestEvent;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type DbWeight = ();
+    type BlockExecutionWeight = ();
+    type ExtrinsicBaseWeight = ();
+    type MaximumExtrinsicWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type PalletInfo = ();
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+    pub const MaxLocks: u32 = 10;
+}
+
+impl pallet_balances::Trait for Test {
+    type Balance = u64;
+    type Event = TestEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MintUnit: u64 = 100;
+    pub const Cooldown: u64 = 10;
+    pub const MintPeriod: u64 = 5;
+    pub const MintsPerPeriod: u32 = 2;
+    pub const Difficulty: u32 = 4;
+}
+
+impl faucet::Trait for Test {
+    type Event = TestEvent;
+    type Currency = Balances;
+    type MintUnit = MintUnit;
+    type Cooldown = Cooldown;
+    type MintPeriod = MintPeriod;
+    type MintsPerPeriod = MintsPerPeriod;
+    type Difficulty = Difficulty;
+    type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Faucet = faucet::Module<Test>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Finds a proof of work for the next mint of `account`.
+pub fn solve(account: u64) -> u64 {
+    let next_mint = Faucet::next_mint(account);
+    (0..)
+        .find(|nonce| {
+            faucet::leading_zeros(&faucet::pow_hash(&account, next_mint, *nonce))
+                >= Difficulty::get()
+        })
+        .unwrap()
+}
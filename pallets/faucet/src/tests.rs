@@ -0,0 +1,85 @@
+use crate::mock::*;
+use crate::{Call, Error, RawEvent};
+use frame_support::unsigned::ValidateUnsigned;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
+
+#[test]
+fn mint_deposits_mint_unit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Faucet::mint(Origin::none(), 1, solve(1)));
+        assert_eq!(Balances::free_balance(1), MintUnit::get());
+        assert_eq!(Faucet::next_mint(1), 1 + Cooldown::get());
+        assert_eq!(Faucet::period_mints(), 1);
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == TestEvent::faucet(RawEvent::Minted(1, 100))));
+    });
+}
+
+#[test]
+fn mint_is_unsigned() {
+    new_test_ext().execute_with(|| {
+        assert!(Faucet::mint(Origin::signed(1), 1, solve(1)).is_err());
+    });
+}
+
+#[test]
+fn mint_requires_proof_of_work() {
+    new_test_ext().execute_with(|| {
+        let nonce = (0..)
+            .find(|nonce| {
+                crate::leading_zeros(&crate::pow_hash(&1u64, 0u64, *nonce)) < Difficulty::get()
+            })
+            .unwrap();
+        assert_noop!(
+            Faucet::mint(Origin::none(), 1, nonce),
+            Error::<Test>::InvalidProof
+        );
+    });
+}
+
+#[test]
+fn mint_respects_cooldown() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Faucet::mint(Origin::none(), 1, solve(1)));
+        System::set_block_number(Cooldown::get());
+        assert_noop!(
+            Faucet::mint(Origin::none(), 1, solve(1)),
+            Error::<Test>::Cooldown
+        );
+        System::set_block_number(1 + Cooldown::get());
+        assert_ok!(Faucet::mint(Origin::none(), 1, solve(1)));
+        assert_eq!(Balances::free_balance(1), 2 * MintUnit::get());
+    });
+}
+
+#[test]
+fn mint_respects_period_limit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Faucet::mint(Origin::none(), 1, solve(1)));
+        assert_ok!(Faucet::mint(Origin::none(), 2, solve(2)));
+        assert_noop!(
+            Faucet::mint(Origin::none(), 3, solve(3)),
+            Error::<Test>::PeriodLimit
+        );
+        System::set_block_number(1 + MintPeriod::get());
+        assert_ok!(Faucet::mint(Origin::none(), 3, solve(3)));
+        assert_eq!(Faucet::period_start(), 1 + MintPeriod::get());
+        assert_eq!(Faucet::period_mints(), 1);
+    });
+}
+
+#[test]
+fn validate_unsigned_checks_mint() {
+    new_test_ext().execute_with(|| {
+        let call = Call::mint(1, solve(1));
+        assert!(Faucet::validate_unsigned(TransactionSource::External, &call).is_ok());
+        assert_ok!(Faucet::mint(Origin::none(), 1, solve(1)));
+        let call = Call::mint(1, solve(1));
+        assert_eq!(
+            Faucet::validate_unsigned(TransactionSource::External, &call),
+            Err(InvalidTransaction::Custom(Error::<Test>::Cooldown.as_u8()).into())
+        );
+    });
+}
@@ -0,0 +1,23 @@
+//! Runtime api for resolving identities.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use parity_scale_codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    pub trait IdentityApi<AccountId, Uid, Cid>
+    where
+        AccountId: Codec,
+        Uid: Codec,
+        Cid: Codec,
+    {
+        /// Uid the device `account` belongs to.
+        fn uid(account: AccountId) -> Option<Uid>;
+
+        /// Devices of `uid`.
+        fn devices(uid: Uid) -> Vec<AccountId>;
+
+        /// Head of the claim chain of `uid`.
+        fn identity(uid: Uid) -> Option<Cid>;
+    }
+}
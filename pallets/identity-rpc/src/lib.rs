@@ -0,0 +1,91 @@
+//! Json rpc for resolving identities.
+//!
+//! Github handles are proven by claims in the offchain claim chain of a uid,
+//! which the node can't read. They are resolved by `GithubClient` of the
+//! client and by the `/github/:handle` route of the indexer.
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use parity_scale_codec::Codec;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Block as BlockT;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::sync::Arc;
+pub use sunshine_identity_rpc_runtime_api::IdentityApi as IdentityRuntimeApi;
+
+#[rpc]
+pub trait IdentityApi<BlockHash, AccountId, Uid> {
+    /// Uid the device `account` belongs to.
+    #[rpc(name = "identity_uid")]
+    fn uid(&self, account: AccountId, at: Option<BlockHash>) -> Result<Option<Uid>>;
+
+    /// Devices of `uid`.
+    #[rpc(name = "identity_devices")]
+    fn devices(&self, uid: Uid, at: Option<BlockHash>) -> Result<Vec<AccountId>>;
+
+    /// Cid of the head of the claim chain of `uid`.
+    #[rpc(name = "identity_head")]
+    fn head(&self, uid: Uid, at: Option<BlockHash>) -> Result<Option<String>>;
+}
+
+/// Implements the identity rpc with the runtime api of `C`.
+pub struct Identity<C, B, Cid> {
+    client: Arc<C>,
+    _marker: PhantomData<(B, Cid)>,
+}
+
+impl<C, B, Cid> Identity<C, B, Cid> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: PhantomData,
+        }
+    }
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(1),
+        message: "Unable to query the runtime api.".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
+impl<C, Block, AccountId, Uid, Cid> IdentityApi<<Block as BlockT>::Hash, AccountId, Uid>
+    for Identity<C, Block, Cid>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: IdentityRuntimeApi<Block, AccountId, Uid, Cid>,
+    AccountId: Codec,
+    Uid: Codec,
+    Cid: Codec + Display + Send + Sync + 'static,
+{
+    fn uid(&self, account: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Uid>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .uid(&at, account)
+            .map_err(runtime_error)
+    }
+
+    fn devices(&self, uid: Uid, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<AccountId>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .devices(&at, uid)
+            .map_err(runtime_error)
+    }
+
+    fn head(&self, uid: Uid, at: Option<<Block as BlockT>::Hash>) -> Result<Option<String>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let head = self
+            .client
+            .runtime_api()
+            .identity(&at, uid)
+            .map_err(runtime_error)?;
+        Ok(head.map(|cid| cid.to_string()))
+    }
+}
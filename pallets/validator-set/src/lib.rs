@@ -0,0 +1,204 @@
+//! Proof of authority validator set.
+//!
+//! The validators are managed by the `ManagerOrigin` and take effect with the
+//! session rotation after the next one, as `pallet_session` queues the
+//! validator set one session ahead. A validator has to register its session
+//! keys with `Session::set_keys` before it is added, since a validator without
+//! keys can't author blocks or vote on finality.
+//!
+//! The validator set is handed to the session on every rotation, so that the
+//! historical session keeps a root of every session to prove key ownership
+//! against. Validators reported for an offence are removed from the set,
+//! unless that would leave fewer than `MinValidators`.
+//!
+//! Validators don't have a stake, so offences aren't slashed. The slash
+//! fraction is only recorded in the `ValidatorOffence` event. Like any other
+//! removal, the removal of an offender takes effect two sessions later. Until
+//! then the offender keeps authoring blocks and voting on finality. Its
+//! further offences are still reported, with `removed` set to false.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::storage::unhashed;
+use frame_support::traits::{EnsureOrigin, Get};
+use frame_support::weights::Weight;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure, StorageHasher, Twox128, Twox64Concat,
+};
+use pallet_session::SessionManager;
+use parity_scale_codec::Encode;
+use sp_runtime::{DispatchResult, Perbill};
+use sp_staking::offence::{OffenceDetails, OnOffenceHandler};
+use sp_staking::SessionIndex;
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The validators are identified by their account in the session.
+pub trait Trait:
+    pallet_session::Trait<ValidatorId = <Self as frame_system::Trait>::AccountId>
+{
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Origin that adds and removes validators.
+    type ManagerOrigin: EnsureOrigin<Self::Origin>;
+    /// Minimum number of validators.
+    type MinValidators: Get<u32>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as ValidatorSet {
+        /// Validators of the next session rotation.
+        pub Validators get(fn validators) config(): Vec<T::AccountId>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        <T as frame_system::Trait>::AccountId,
+    {
+        /// A validator was added. \[validator\]
+        ValidatorAdded(AccountId),
+        /// A validator was removed. \[validator\]
+        ValidatorRemoved(AccountId),
+        /// A validator was reported for an offence. \[validator, slash fraction, removed\]
+        ValidatorOffence(AccountId, Perbill, bool),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// The account is already a validator.
+        AlreadyValidator,
+        /// The account isn't a validator.
+        NotValidator,
+        /// Removing the validator would leave fewer than `MinValidators`.
+        TooFewValidators,
+        /// The account didn't set its session keys.
+        NoSessionKeys,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        const MinValidators: u32 = T::MinValidators::get();
+
+        /// Adds a validator that set its session keys.
+        #[weight = 50_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+        pub fn add_validator(origin, validator: T::AccountId) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+            let mut validators = Self::validators();
+            ensure!(!validators.contains(&validator), Error::<T>::AlreadyValidator);
+            ensure!(Self::has_session_keys(&validator), Error::<T>::NoSessionKeys);
+            validators.push(validator.clone());
+            Validators::<T>::put(validators);
+            Self::deposit_event(RawEvent::ValidatorAdded(validator));
+            Ok(())
+        }
+
+        /// Removes a validator.
+        #[weight = 50_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn remove_validator(origin, validator: T::AccountId) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+            Self::remove(&validator)?;
+            Self::deposit_event(RawEvent::ValidatorRemoved(validator));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Whether `validator` set its session keys for the next session.
+    ///
+    /// `pallet_session` doesn't expose the `NextKeys` map, so its key is built
+    /// from the storage prefix and the hasher of the map.
+    fn has_session_keys(validator: &T::AccountId) -> bool {
+        let mut key = Twox128::hash(b"Session").to_vec();
+        key.extend_from_slice(&Twox128::hash(b"NextKeys"));
+        key.extend(validator.using_encoded(Twox64Concat::hash));
+        unhashed::exists(&key)
+    }
+
+    fn remove(validator: &T::AccountId) -> Result<(), Error<T>> {
+        let mut validators = Self::validators();
+        let index = validators
+            .iter()
+            .position(|v| v == validator)
+            .ok_or(Error::<T>::NotValidator)?;
+        ensure!(
+            validators.len() as u32 > T::MinValidators::get(),
+            Error::<T>::TooFewValidators
+        );
+        validators.remove(index);
+        Validators::<T>::put(validators);
+        Ok(())
+    }
+}
+
+impl<T: Trait> SessionManager<T::AccountId> for Module<T> {
+    fn new_session(_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+        let validators = Self::validators();
+        // The validators aren't initialized yet when the session builds its
+        // genesis, which then uses the validators of its own genesis config.
+        if validators.is_empty() {
+            None
+        } else {
+            Some(validators)
+        }
+    }
+
+    fn end_session(_index: SessionIndex) {}
+
+    fn start_session(_index: SessionIndex) {}
+}
+
+/// The validator account is its own full identification.
+impl<T: Trait> pallet_session::historical::SessionManager<T::AccountId, T::AccountId>
+    for Module<T>
+{
+    fn new_session(index: SessionIndex) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+        <Self as SessionManager<_>>::new_session(index).map(|validators| {
+            validators
+                .into_iter()
+                .map(|validator| (validator.clone(), validator))
+                .collect()
+        })
+    }
+
+    fn end_session(_index: SessionIndex) {}
+
+    fn start_session(_index: SessionIndex) {}
+}
+
+/// Removes the offenders from the validators of the next session rotation.
+/// The offenders stay in the current and the queued validator set.
+impl<T: Trait, I> OnOffenceHandler<T::AccountId, (T::AccountId, I), Weight> for Module<T> {
+    fn on_offence(
+        offenders: &[OffenceDetails<T::AccountId, (T::AccountId, I)>],
+        slash_fraction: &[Perbill],
+        _session: SessionIndex,
+    ) -> Result<Weight, ()> {
+        for (details, fraction) in offenders.iter().zip(slash_fraction) {
+            let validator = &details.offender.0;
+            let removed = Self::remove(validator).is_ok();
+            Self::deposit_event(RawEvent::ValidatorOffence(
+                validator.clone(),
+                *fraction,
+                removed,
+            ));
+        }
+        let count = offenders.len() as Weight;
+        Ok(T::DbWeight::get().reads_writes(count, count))
+    }
+
+    fn can_report() -> bool {
+        true
+    }
+}
@@ -0,0 +1,118 @@
+//! Test runtime of the validator set pallet.
+use crate as validator_set;
+use frame_support::weights::Weight;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system::{self as system, EnsureRoot};
+use sp_core::H256;
+use sp_runtime::testing::{Header, UintAuthorityId};
+use sp_runtime::traits::{BlakeTwo256, ConvertInto, IdentityLookup};
+use sp_runtime::Perbill;
+
+impl_outer_origin! {
+    pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+    pub enum TestEvent for Test {
+        system<T>,
+        pallet_session,
+        validator_set<T>,
+    }
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const MaximumBlockWeight: Weight = 1024;
+    pub const MaximumBlockLength: u32 = 2 * 1024;
+    pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+    type BaseCallFilter = ();
+    type Origin = Origin;
+    type Call = ();
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = TestEvent;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type DbWeight = ();
+    type BlockExecutionWeight = ();
+    type ExtrinsicBaseWeight = ();
+    type MaximumExtrinsicWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type PalletInfo = ();
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+}
+
+parameter_types! {
+    pub const SessionPeriod: u64 = 10;
+    pub const SessionOffset: u64 = 0;
+    pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(33);
+}
+
+impl pallet_session::Trait for Test {
+    type Event = TestEvent;
+    type ValidatorId = u64;
+    type ValidatorIdOf = ConvertInto;
+    type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type SessionManager = ValidatorSet;
+    type SessionHandler = ();
+    type Keys = UintAuthorityId;
+    type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MinValidators: u32 = 2;
+}
+
+impl validator_set::Trait for Test {
+    type Event = TestEvent;
+    type ManagerOrigin = EnsureRoot<u64>;
+    type MinValidators = MinValidators;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Session = pallet_session::Module<Test>;
+pub type ValidatorSet = validator_set::Module<Test>;
+
+/// Accounts 1, 2 and 3 are the validators.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    validator_set::GenesisConfig::<Test> {
+        validators: vec![1, 2, 3],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Validator set events deposited so far.
+pub fn validator_set_events() -> Vec<validator_set::Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            TestEvent::validator_set(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,133 @@
+use crate::mock::*;
+use crate::{Error, RawEvent};
+use frame_support::weights::Weight;
+use frame_support::{assert_noop, assert_ok};
+use pallet_session::SessionManager;
+use sp_runtime::testing::UintAuthorityId;
+use sp_runtime::Perbill;
+use sp_staking::offence::{OffenceDetails, OnOffenceHandler};
+
+/// Sets the session keys of `validator`.
+fn set_keys(validator: u64) {
+    assert_ok!(Session::set_keys(
+        Origin::signed(validator),
+        UintAuthorityId(validator),
+        vec![]
+    ));
+}
+
+/// Reports an offence of `offender` to the validator set.
+fn report(offender: u64, fraction: Perbill) {
+    let details = OffenceDetails {
+        offender: (offender, offender),
+        reporters: vec![],
+    };
+    let result = <ValidatorSet as OnOffenceHandler<u64, (u64, u64), Weight>>::on_offence(
+        &[details],
+        &[fraction],
+        0,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn manager_adds_and_removes_validators() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorSet::add_validator(Origin::signed(1), 4),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        set_keys(4);
+        assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+        assert_noop!(
+            ValidatorSet::add_validator(Origin::root(), 4),
+            Error::<Test>::AlreadyValidator
+        );
+        assert_ok!(ValidatorSet::remove_validator(Origin::root(), 1));
+        assert_noop!(
+            ValidatorSet::remove_validator(Origin::root(), 1),
+            Error::<Test>::NotValidator
+        );
+        assert_eq!(ValidatorSet::validators(), vec![2, 3, 4]);
+    });
+}
+
+#[test]
+fn validator_needs_session_keys() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorSet::add_validator(Origin::root(), 4),
+            Error::<Test>::NoSessionKeys
+        );
+        set_keys(4);
+        assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+        assert_eq!(ValidatorSet::validators(), vec![1, 2, 3, 4]);
+    });
+}
+
+#[test]
+fn remove_keeps_min_validators() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorSet::remove_validator(Origin::root(), 1));
+        assert_noop!(
+            ValidatorSet::remove_validator(Origin::root(), 2),
+            Error::<Test>::TooFewValidators
+        );
+    });
+}
+
+#[test]
+fn new_session_hands_over_validators() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorSet::remove_validator(Origin::root(), 1));
+        assert_eq!(
+            <ValidatorSet as SessionManager<u64>>::new_session(1),
+            Some(vec![2, 3])
+        );
+    });
+}
+
+#[test]
+fn offender_is_removed() {
+    new_test_ext().execute_with(|| {
+        report(2, Perbill::from_percent(10));
+        assert_eq!(ValidatorSet::validators(), vec![1, 3]);
+        assert_eq!(
+            validator_set_events(),
+            vec![RawEvent::ValidatorOffence(
+                2,
+                Perbill::from_percent(10),
+                true
+            )]
+        );
+    });
+}
+
+#[test]
+fn offender_is_kept_at_min_validators() {
+    new_test_ext().execute_with(|| {
+        report(1, Perbill::zero());
+        report(2, Perbill::zero());
+        assert_eq!(ValidatorSet::validators(), vec![2, 3]);
+        assert_eq!(
+            validator_set_events().last(),
+            Some(&RawEvent::ValidatorOffence(2, Perbill::zero(), false))
+        );
+    });
+}
+
+#[test]
+fn repeated_offence_removes_once() {
+    new_test_ext().execute_with(|| {
+        report(1, Perbill::zero());
+        report(1, Perbill::zero());
+        assert_eq!(ValidatorSet::validators(), vec![2, 3]);
+        assert_eq!(
+            validator_set_events(),
+            vec![
+                RawEvent::ValidatorOffence(1, Perbill::zero(), true),
+                RawEvent::ValidatorOffence(1, Perbill::zero(), false),
+            ]
+        );
+    });
+}
@@ -0,0 +1,94 @@
+//! Benchmarks for the bounty pallet.
+use super::{cid, funded_account};
+use crate::{
+    AccountId, Balance, Balances, BountyId, Cid, Event, MinContribution, MinDeposit, SubmissionId,
+    System,
+};
+use frame_benchmarking::benchmarks;
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+use sunshine_bounty::{Call, RawEvent};
+
+pub struct Module<T: Trait>(sunshine_bounty::Module<T>);
+
+/// Pins the types of the pallet to the ones of the runtime.
+pub trait Trait:
+    sunshine_bounty::Trait<
+        IpfsReference = Cid,
+        BountyId = BountyId,
+        SubmissionId = SubmissionId,
+        Currency = Balances,
+    > + frame_system::Trait<AccountId = AccountId>
+{
+}
+
+/// Returns the id of the last posted bounty.
+fn last_bounty() -> Result<BountyId, &'static str> {
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            Event::sunshine_bounty(RawEvent::BountyPosted(_, id, ..)) => Some(id),
+            _ => None,
+        })
+        .ok_or("no bounty posted")
+}
+
+/// Returns the id of the last submission.
+fn last_submission() -> Result<SubmissionId, &'static str> {
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            Event::sunshine_bounty(RawEvent::BountySubmissionPosted(_, _, _, id, ..)) => Some(id),
+            _ => None,
+        })
+        .ok_or("no submission posted")
+}
+
+/// Posts a bounty with a deposit of `amount`.
+fn post<T: Trait>(amount: Balance) -> Result<(AccountId, BountyId), &'static str> {
+    let poster = funded_account("poster", 0);
+    sunshine_bounty::Module::<T>::post_bounty(
+        RawOrigin::Signed(poster.clone()).into(),
+        cid(0),
+        amount,
+    )?;
+    Ok((poster, last_bounty()?))
+}
+
+/// Submits work asking for `amount` to bounty `id`.
+fn submit<T: Trait>(id: BountyId, amount: Balance) -> Result<SubmissionId, &'static str> {
+    let submitter = funded_account("submitter", 0);
+    sunshine_bounty::Module::<T>::submit_for_bounty(
+        RawOrigin::Signed(submitter).into(),
+        id,
+        cid(1),
+        amount,
+    )?;
+    last_submission()
+}
+
+benchmarks! {
+    _ { }
+
+    post_bounty {
+        let poster = funded_account("poster", 0);
+    }: _(RawOrigin::Signed(poster), cid(0), MinDeposit::get())
+
+    contribute_to_bounty {
+        let (_, id) = post::<T>(MinDeposit::get())?;
+        let contributor = funded_account("contributor", 0);
+    }: _(RawOrigin::Signed(contributor), id, MinContribution::get())
+
+    submit_for_bounty {
+        let (_, id) = post::<T>(MinDeposit::get())?;
+        let submitter = funded_account("submitter", 0);
+    }: _(RawOrigin::Signed(submitter), id, cid(1), MinDeposit::get())
+
+    // Paying out the whole deposit closes the bounty.
+    approve_bounty_submission {
+        let (poster, id) = post::<T>(MinDeposit::get())?;
+        let submission_id = submit::<T>(id, MinDeposit::get())?;
+    }: _(RawOrigin::Signed(poster), submission_id)
+}
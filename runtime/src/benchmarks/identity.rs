@@ -0,0 +1,54 @@
+//! Benchmarks for the identity pallet.
+use super::{cid, funded_account};
+use crate::{AccountId, Cid, Identity};
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+use sunshine_identity_pallet::Call;
+
+pub struct Module<T: Trait>(sunshine_identity_pallet::Module<T>);
+
+/// Pins the types of the pallet to the ones of the runtime.
+pub trait Trait:
+    sunshine_identity_pallet::Trait<Cid = Cid, Mask = [u8; 32], Gen = u16>
+    + frame_system::Trait<AccountId = AccountId>
+{
+}
+
+/// Returns a funded account, which is the first device of a new uid.
+fn device(index: u32) -> Result<AccountId, &'static str> {
+    let account = funded_account("device", index);
+    Identity::uid_lookup(&account).ok_or("funded account without uid")?;
+    Ok(account)
+}
+
+benchmarks! {
+    _ { }
+
+    create_account_for {
+        let creator = device(0)?;
+        let key = account("key", 0, 0);
+    }: _(RawOrigin::Signed(creator), key)
+
+    add_key {
+        let owner = device(0)?;
+        let key = account("key", 0, 0);
+    }: _(RawOrigin::Signed(owner), key)
+
+    remove_key {
+        let owner = device(0)?;
+        let key: AccountId = account("key", 0, 0);
+        sunshine_identity_pallet::Module::<T>::add_key(
+            RawOrigin::Signed(owner.clone()).into(),
+            key.clone(),
+        )?;
+    }: _(RawOrigin::Signed(owner), key)
+
+    change_password {
+        let owner = device(0)?;
+    }: _(RawOrigin::Signed(owner), [0; 32], 1)
+
+    set_identity {
+        let owner = device(0)?;
+    }: _(RawOrigin::Signed(owner), None, cid(0))
+}
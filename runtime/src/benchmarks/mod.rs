@@ -0,0 +1,34 @@
+//! Benchmarks for the pallets maintained outside of this repository.
+//!
+//! The bounty and identity pallets declare their weights in their own
+//! repositories and have no `WeightInfo` the runtime could plug generated
+//! weights into. Their calls are benchmarked against this runtime, so that the
+//! weights declared upstream can be updated from the results.
+#![cfg(feature = "runtime-benchmarks")]
+
+use crate::{AccountId, Balance, Balances, Cid};
+use frame_benchmarking::account;
+use frame_support::traits::Currency;
+use parity_scale_codec::Encode;
+use sp_std::convert::TryFrom;
+use sp_std::prelude::*;
+
+pub mod bounty;
+pub mod identity;
+
+const SEED: u32 = 0;
+
+/// Returns an account with enough funds for any call.
+fn funded_account(name: &'static str, index: u32) -> AccountId {
+    let account = account(name, index, SEED);
+    Balances::make_free_balance_be(&account, Balance::max_value() / 2);
+    account
+}
+
+/// Returns the cid of a raw blake2b-256 block, distinct for every `seed`.
+fn cid(seed: u32) -> Cid {
+    // cidv1, raw codec, blake2b-256 multihash of 32 bytes
+    let mut bytes = vec![0x01, 0x55, 0xa0, 0xe4, 0x02, 0x20];
+    bytes.extend_from_slice(&sp_io::hashing::blake2_256(&seed.encode()));
+    Cid::try_from(&bytes[..]).expect("valid cid; qed")
+}
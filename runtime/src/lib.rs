@@ -8,35 +8,45 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use frame_support::{
     construct_runtime, parameter_types,
-    traits::{KeyOwnerProofSystem, Randomness},
+    traits::{Get, InstanceFilter, KeyOwnerProofSystem, Randomness},
     weights::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
-        IdentityFee, Weight,
+        Weight, WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
     },
 };
+use frame_system::{EnsureOneOf, EnsureRoot};
 use pallet_grandpa::{
     fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
+use pallet_session::historical as pallet_session_historical;
+use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
+use parity_scale_codec::{Decode, Encode};
+use smallvec::smallvec;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
-use sp_runtime::Perbill;
+use sp_core::{
+    crypto::KeyTypeId,
+    u32_trait::{_1, _2},
+    OpaqueMetadata,
+};
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     traits::{
-        BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, NumberFor, Saturating,
-        Verify,
+        BlakeTwo256, Block as BlockT, ConvertInto, IdentifyAccount, NumberFor, OpaqueKeys,
+        Saturating, StaticLookup, Verify,
     },
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, ModuleId, MultiSignature,
+    ApplyExtrinsicResult, ModuleId, MultiSignature, RuntimeDebug,
 };
+use sp_runtime::{FixedPointNumber, Perbill, Percent, Permill, Perquintill};
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
-pub type Address = AccountId;
+pub type AccountIndex = u32;
+pub type Address = <Indices as StaticLookup>::Source;
 pub type Balance = u128;
 pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 pub type BlockId = generic::BlockId<Block>;
@@ -45,12 +55,19 @@ pub type BountyId = u64;
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 pub type Cid = sunshine_codec::Cid;
 pub type DigestItem = generic::DigestItem<Hash>;
+pub type EnsureRootOrHalfCouncil = EnsureOneOf<
+    AccountId,
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>,
+>;
+pub type EscrowId = u64;
 pub type Executive = frame_executive::Executive<
     Runtime,
     Block,
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllModules,
+    migrations::Migrations,
 >;
 pub type Index = u32;
 pub type Hash = sp_core::H256;
@@ -67,8 +84,15 @@ pub type SignedExtra = (
     pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
 );
 pub type SubmissionId = u64;
+pub type Uid = u32;
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 
+mod benchmarks;
+mod migrations;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
 pub mod opaque {
     use super::*;
 
@@ -91,10 +115,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("sunshine"),
     impl_name: create_runtime_str!("sunshine"),
     authoring_version: 1,
-    spec_version: 1,
+    spec_version: 17,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 7,
 };
 
 /// The version information used to identify this runtime when compiled natively.
@@ -132,7 +156,7 @@ impl frame_system::Trait for Runtime {
     /// The aggregated dispatch type that is available for extrinsics.
     type Call = Call;
     /// The lookup mechanism to get account ID from whatever is passed in dispatchers.
-    type Lookup = IdentityLookup<AccountId>;
+    type Lookup = Indices;
     /// The index type for storing how many extrinsics an account has signed.
     type Index = Index;
     /// The index type for blocks.
@@ -202,14 +226,80 @@ impl pallet_aura::Trait for Runtime {
 impl pallet_grandpa::Trait for Runtime {
     type Event = Event;
     type Call = Call;
-    type KeyOwnerProofSystem = ();
+    type KeyOwnerProofSystem = Historical;
     type KeyOwnerProof =
         <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
     type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
         KeyTypeId,
         GrandpaId,
     )>>::IdentificationTuple;
-    type HandleEquivocation = ();
+    type HandleEquivocation =
+        pallet_grandpa::EquivocationHandler<Self::KeyOwnerIdentification, Offences>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const SessionPeriod: BlockNumber = HOURS;
+    pub const SessionOffset: BlockNumber = 0;
+    pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl pallet_session::Trait for Runtime {
+    type Event = Event;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = ConvertInto;
+    type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+    type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+    type Keys = opaque::SessionKeys;
+    type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+    type WeightInfo = ();
+}
+
+impl pallet_session::historical::Trait for Runtime {
+    type FullIdentification = AccountId;
+    type FullIdentificationOf = ConvertInto;
+}
+
+parameter_types! {
+    pub OffencesWeightSoftLimit: Weight = Perbill::from_percent(60) * MaximumBlockWeight::get();
+}
+
+impl pallet_offences::Trait for Runtime {
+    type Event = Event;
+    type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+    type OnOffenceHandler = ValidatorSet;
+    type WeightSoftLimit = OffencesWeightSoftLimit;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+    Call: From<C>,
+{
+    type Extrinsic = UncheckedExtrinsic;
+    type OverarchingCall = Call;
+}
+
+parameter_types! {
+    pub const MinValidators: u32 = 1;
+}
+
+impl sunshine_validator_set_pallet::Trait for Runtime {
+    type Event = Event;
+    type ManagerOrigin = EnsureRootOrHalfCouncil;
+    type MinValidators = MinValidators;
+}
+
+parameter_types! {
+    pub const IndexDeposit: Balance = 1_000_000_000;
+}
+
+impl pallet_indices::Trait for Runtime {
+    type AccountIndex = AccountIndex;
+    type Currency = Balances;
+    type Deposit = IndexDeposit;
+    type Event = Event;
     type WeightInfo = ();
 }
 
@@ -224,26 +314,149 @@ impl pallet_balances::Trait for Runtime {
     type ExistentialDeposit = ExistentialDeposit;
     type MaxLocks = MaxLocks;
     type AccountStore = Identity;
-    type DustRemoval = ();
+    type DustRemoval = Treasury;
     type WeightInfo = ();
 }
 
+/// Fee of an extrinsic with `ExtrinsicBaseWeight`, a thousandth of what the
+/// faucet mints.
+pub const EXTRINSIC_BASE_FEE: Balance = 1_000_000;
+
+/// Converts weight to fee, so that an extrinsic with `ExtrinsicBaseWeight`
+/// costs `EXTRINSIC_BASE_FEE`.
+///
+/// The curve is linear on purpose. Coefficients are applied to the raw
+/// weight and the smallest non-zero quadratic coefficient, a billionth, would
+/// already make an extrinsic with the base weight cost 15 times as much and
+/// the heaviest extrinsic 10^5 times as much. Heavy use of block space is
+/// priced by the fee multiplier of `TargetedFeeAdjustment` instead.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+    type Balance = Balance;
+
+    fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+        let p = EXTRINSIC_BASE_FEE;
+        let q = Balance::from(ExtrinsicBaseWeight::get());
+        smallvec![WeightToFeeCoefficient {
+            degree: 1,
+            negative: false,
+            coeff_frac: Perbill::from_rational_approximation(p % q, q),
+            coeff_integer: p / q,
+        }]
+    }
+}
+
 parameter_types! {
-    pub const TransactionByteFee: Balance = 1;
+    pub const TransactionByteFee: Balance = 1_000;
+    /// The fee multiplier rises while blocks are fuller than this fraction of
+    /// the weight available to normal extrinsics.
+    pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+    pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(3, 100_000);
+    pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000u128);
 }
 
 impl pallet_transaction_payment::Trait for Runtime {
     type Currency = pallet_balances::Module<Runtime>;
-    type OnTransactionPayment = ();
+    type OnTransactionPayment = Treasury;
     type TransactionByteFee = TransactionByteFee;
-    type WeightToFee = IdentityFee<Balance>;
-    type FeeMultiplierUpdate = ();
+    type WeightToFee = WeightToFee;
+    type FeeMultiplierUpdate =
+        TargetedFeeAdjustment<Self, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
+}
+
+parameter_types! {
+    pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+    pub const CouncilMaxProposals: u32 = 100;
+    pub const CouncilMaxMembers: u32 = 100;
+}
+pub type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Trait<CouncilCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
+    type Event = Event;
+    type MotionDuration = CouncilMotionDuration;
+    type MaxProposals = CouncilMaxProposals;
+    type MaxMembers = CouncilMaxMembers;
+    type DefaultVote = pallet_collective::PrimeDefaultVote;
+    type WeightInfo = ();
+}
+
+impl pallet_membership::Trait<pallet_membership::Instance2> for Runtime {
+    type Event = Event;
+    type AddOrigin = EnsureRootOrHalfCouncil;
+    type RemoveOrigin = EnsureRootOrHalfCouncil;
+    type SwapOrigin = EnsureRootOrHalfCouncil;
+    type ResetOrigin = EnsureRootOrHalfCouncil;
+    type PrimeOrigin = EnsureRootOrHalfCouncil;
+    type MembershipInitialized = Council;
+    type MembershipChanged = Council;
+}
+
+parameter_types! {
+    pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+    pub const VotingPeriod: BlockNumber = 7 * DAYS;
+    pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+    pub const EnactmentPeriod: BlockNumber = DAYS;
+    pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+    pub const MinimumDeposit: Balance = 10_000_000_000;
+    pub const PreimageByteDeposit: Balance = 1_000;
+    pub const InstantAllowed: bool = true;
+    pub const MaxVotes: u32 = 100;
+    pub const MaxProposals: u32 = 100;
+}
+impl pallet_democracy::Trait for Runtime {
+    type Proposal = Call;
+    type Event = Event;
+    type Currency = Balances;
+    type EnactmentPeriod = EnactmentPeriod;
+    type LaunchPeriod = LaunchPeriod;
+    type VotingPeriod = VotingPeriod;
+    type MinimumDeposit = MinimumDeposit;
+    type ExternalOrigin = EnsureRootOrHalfCouncil;
+    type ExternalMajorityOrigin = EnsureRootOrHalfCouncil;
+    type ExternalDefaultOrigin =
+        pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+    type FastTrackOrigin = EnsureRootOrHalfCouncil;
+    type InstantOrigin =
+        pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+    type InstantAllowed = InstantAllowed;
+    type FastTrackVotingPeriod = FastTrackVotingPeriod;
+    type CancellationOrigin = EnsureRootOrHalfCouncil;
+    type VetoOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+    type CooloffPeriod = CooloffPeriod;
+    type PreimageByteDeposit = PreimageByteDeposit;
+    type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+    type Slash = Treasury;
+    type Scheduler = Scheduler;
+    type PalletsOrigin = OriginCaller;
+    type MaxVotes = MaxVotes;
+    type MaxProposals = MaxProposals;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+    pub const MaxScheduledPerBlock: u32 = 50;
+}
+impl pallet_scheduler::Trait for Runtime {
+    type Event = Event;
+    type Origin = Origin;
+    type PalletsOrigin = OriginCaller;
+    type Call = Call;
+    type MaximumWeight = MaximumSchedulerWeight;
+    type ScheduleOrigin = EnsureRoot<AccountId>;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    type WeightInfo = ();
 }
 
 parameter_types! {
     pub const Foundation: ModuleId = ModuleId(*b"fundacon");
-    pub const MinDeposit: u128 = 10;
-    pub const MinContribution: u128 = 5;
+    /// Stored on chain at `twox_128(":MinDeposit:")`, so governance can change
+    /// it with `system.set_storage`.
+    pub storage MinDeposit: u128 = 10;
+    /// Stored on chain at `twox_128(":MinContribution:")`.
+    pub storage MinContribution: u128 = 5;
 }
 impl sunshine_bounty::Trait for Runtime {
     type Event = Event;
@@ -256,13 +469,216 @@ impl sunshine_bounty::Trait for Runtime {
     type MinContribution = MinContribution;
 }
 
+/// The governed `MinDeposit`, raised to the existential deposit since the
+/// deposit creates the escrow account.
+pub struct MinEscrowDeposit;
+
+impl Get<Balance> for MinEscrowDeposit {
+    fn get() -> Balance {
+        MinDeposit::get().max(ExistentialDeposit::get())
+    }
+}
+
+parameter_types! {
+    pub const MaxMilestones: u32 = 16;
+    pub const MinEscrowLifetime: BlockNumber = DAYS;
+    pub const MaxEscrowLifetime: BlockNumber = 365 * DAYS;
+    pub const MaxEscrowContributors: u32 = 100;
+    pub const MaxEscrowDisputes: u32 = 20;
+    pub const MaxEscrowSubmissions: u32 = 50;
+    /// Expiring the maximum number of bounties with the maximum number of
+    /// contributors, disputes and submissions takes about a quarter of a
+    /// block.
+    pub const MaxEscrowExpirations: u32 = 10;
+    pub const DisputeBond: u128 = 10_000;
+    pub const MaxApprovers: u32 = 16;
+    pub const MaxVestingPeriod: BlockNumber = 4 * 365 * DAYS;
+    pub const MaxVestingSchedules: u32 = 8;
+}
+
+/// Resolves the uid of a device with the identity pallet.
+pub struct DeviceUid;
+
+impl sunshine_escrow_pallet::UidLookup<AccountId, Uid> for DeviceUid {
+    fn uid(account: &AccountId) -> Option<Uid> {
+        Identity::uid_lookup(account)
+    }
+}
+
+impl sunshine_escrow_pallet::Trait for Runtime {
+    type Event = Event;
+    type IpfsReference = Cid;
+    type Currency = Balances;
+    type EscrowId = EscrowId;
+    type SubmissionId = SubmissionId;
+    type Uid = Uid;
+    type UidLookup = DeviceUid;
+    type MaxApprovers = MaxApprovers;
+    type MaxVestingPeriod = MaxVestingPeriod;
+    type MaxVestingSchedules = MaxVestingSchedules;
+    type Foundation = Foundation;
+    type MinDeposit = MinEscrowDeposit;
+    type MinContribution = MinContribution;
+    type MaxMilestones = MaxMilestones;
+    type MinLifetime = MinEscrowLifetime;
+    type MaxLifetime = MaxEscrowLifetime;
+    type MaxContributors = MaxEscrowContributors;
+    type MaxDisputes = MaxEscrowDisputes;
+    type MaxSubmissions = MaxEscrowSubmissions;
+    type MaxExpirations = MaxEscrowExpirations;
+    type DisputeBond = DisputeBond;
+    type Arbiters = ArbiterMembership;
+    type Slashed = Treasury;
+    type WeightInfo = weights::sunshine_escrow_pallet::WeightInfo<Runtime>;
+}
+
+impl pallet_membership::Trait<pallet_membership::Instance1> for Runtime {
+    type Event = Event;
+    type AddOrigin = EnsureRootOrHalfCouncil;
+    type RemoveOrigin = EnsureRootOrHalfCouncil;
+    type SwapOrigin = EnsureRootOrHalfCouncil;
+    type ResetOrigin = EnsureRootOrHalfCouncil;
+    type PrimeOrigin = EnsureRootOrHalfCouncil;
+    type MembershipInitialized = ();
+    type MembershipChanged = ();
+}
+
+parameter_types! {
+    pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+    pub const ProposalBond: Permill = Permill::from_percent(5);
+    pub const ProposalBondMinimum: Balance = 1_000_000_000;
+    pub const SpendPeriod: BlockNumber = 7 * DAYS;
+    /// Funds that weren't spent stay in the treasury.
+    pub const Burn: Permill = Permill::from_percent(0);
+    pub const TipCountdown: BlockNumber = DAYS;
+    pub const TipFindersFee: Percent = Percent::from_percent(20);
+    pub const TipReportDepositBase: Balance = 1_000_000;
+    pub const DataDepositPerByte: Balance = 1_000;
+    pub const BountyDepositBase: Balance = 1_000_000;
+    pub const BountyDepositPayoutDelay: BlockNumber = DAYS;
+    pub const BountyUpdatePeriod: BlockNumber = 14 * DAYS;
+    pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+    pub const BountyValueMinimum: Balance = 10_000_000;
+    pub const MaximumReasonLength: u32 = 16_384;
+}
+impl pallet_treasury::Trait for Runtime {
+    type ModuleId = TreasuryModuleId;
+    type Currency = Balances;
+    type ApproveOrigin = EnsureRootOrHalfCouncil;
+    type RejectOrigin = EnsureRootOrHalfCouncil;
+    type Tippers = CouncilMembership;
+    type TipCountdown = TipCountdown;
+    type TipFindersFee = TipFindersFee;
+    type TipReportDepositBase = TipReportDepositBase;
+    type DataDepositPerByte = DataDepositPerByte;
+    type Event = Event;
+    type OnSlash = Treasury;
+    type ProposalBond = ProposalBond;
+    type ProposalBondMinimum = ProposalBondMinimum;
+    type SpendPeriod = SpendPeriod;
+    type Burn = Burn;
+    type BountyDepositBase = BountyDepositBase;
+    type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+    type BountyUpdatePeriod = BountyUpdatePeriod;
+    type BountyCuratorDeposit = BountyCuratorDeposit;
+    type BountyValueMinimum = BountyValueMinimum;
+    type MaximumReasonLength = MaximumReasonLength;
+    type BurnDestination = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    /// Stored on chain at `twox_128(":MintUnit:")`.
+    pub storage MintUnit: Balance = 1_000_000_000;
+    pub const MintCooldown: BlockNumber = DAYS;
+    pub const MintPeriod: BlockNumber = DAYS;
+    pub const MintsPerPeriod: u32 = 1_000;
+    pub const MintDifficulty: u32 = 16;
+}
+
 impl sunshine_faucet_pallet::Trait for Runtime {
-    const MINT_UNIT: Self::Balance = 1_000_000_000;
     type Event = Event;
+    type Currency = Balances;
+    type MintUnit = MintUnit;
+    type Cooldown = MintCooldown;
+    type MintPeriod = MintPeriod;
+    type MintsPerPeriod = MintsPerPeriod;
+    type Difficulty = MintDifficulty;
+    type WeightInfo = weights::sunshine_faucet_pallet::WeightInfo<Runtime>;
+}
+
+impl pallet_utility::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const ProxyDepositBase: Balance = 1_000_000;
+    pub const ProxyDepositFactor: Balance = 100_000;
+    pub const MaxProxies: u16 = 32;
+    pub const AnnouncementDepositBase: Balance = 1_000_000;
+    pub const AnnouncementDepositFactor: Balance = 200_000;
+    pub const MaxPending: u16 = 32;
+}
+
+/// The calls a proxy may make on behalf of the account that added it.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+    /// Any call.
+    Any,
+    /// Approving and rejecting escrow submissions, alone or batched. The
+    /// escrow pallet only accepts them for bounties the proxied account
+    /// posted.
+    BountyApprover,
+}
+
+impl Default for ProxyType {
+    fn default() -> Self {
+        Self::Any
+    }
+}
+
+impl InstanceFilter<Call> for ProxyType {
+    fn filter(&self, c: &Call) -> bool {
+        match self {
+            ProxyType::Any => true,
+            // The calls of a batch are filtered one by one.
+            ProxyType::BountyApprover => matches!(
+                c,
+                Call::Escrow(sunshine_escrow_pallet::Call::approve(..))
+                    | Call::Escrow(sunshine_escrow_pallet::Call::reject(..))
+                    | Call::Utility(..)
+            ),
+        }
+    }
+
+    fn is_superset(&self, o: &Self) -> bool {
+        match (self, o) {
+            (ProxyType::Any, _) => true,
+            (_, ProxyType::Any) => false,
+            _ => self == o,
+        }
+    }
+}
+
+impl pallet_proxy::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = MaxProxies;
+    type WeightInfo = ();
+    type MaxPending = MaxPending;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
 impl sunshine_identity_pallet::Trait for Runtime {
-    type Uid = u32;
+    type Uid = Uid;
     type Cid = Cid;
     type Mask = [u8; 32];
     type Gen = u16;
@@ -270,6 +686,8 @@ impl sunshine_identity_pallet::Trait for Runtime {
     type Event = Event;
 }
 
+impl migrations::Trait for Runtime {}
+
 construct_runtime!(
     pub enum Runtime where
         Block = Block,
@@ -277,16 +695,31 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic
     {
 
+        ArbiterMembership: pallet_membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
         Aura: pallet_aura::{Module, Config<T>, Inherent},
         Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
         Bounty: sunshine_bounty::{Module, Call, Storage, Event<T>},
-        Faucet: sunshine_faucet_pallet::{Module, Call, Event<T>, ValidateUnsigned},
-        Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
+        Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+        CouncilMembership: pallet_membership::<Instance2>::{Module, Call, Storage, Event<T>, Config<T>},
+        Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
+        Escrow: sunshine_escrow_pallet::{Module, Call, Storage, Event<T>},
+        Faucet: sunshine_faucet_pallet::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+        Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event, ValidateUnsigned},
+        Historical: pallet_session_historical::{Module},
         Identity: sunshine_identity_pallet::{Module, Call, Storage, Event<T>},
+        Indices: pallet_indices::{Module, Call, Storage, Config<T>, Event<T>},
+        Offences: pallet_offences::{Module, Call, Storage, Event},
+        Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
         RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
+        Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
+        Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
+        StorageVersion: migrations::{Module, Config},
         System: frame_system::{Module, Call, Config, Storage, Event<T>},
         Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
         TransactionPayment: pallet_transaction_payment::{Module, Storage},
+        Treasury: pallet_treasury::{Module, Call, Storage, Config, Event<T>},
+        Utility: pallet_utility::{Module, Call, Event},
+        ValidatorSet: sunshine_validator_set_pallet::{Module, Call, Storage, Event<T>, Config<T>},
     }
 );
 
@@ -379,20 +812,133 @@ impl_runtime_apis! {
         }
 
         fn submit_report_equivocation_unsigned_extrinsic(
-            _equivocation_proof: fg_primitives::EquivocationProof<
+            equivocation_proof: fg_primitives::EquivocationProof<
                 <Block as BlockT>::Hash,
                 NumberFor<Block>,
             >,
-            _key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+            key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
         ) -> Option<()> {
-            None
+            let key_owner_proof = key_owner_proof.decode()?;
+            Grandpa::submit_unsigned_equivocation_report(equivocation_proof, key_owner_proof)
         }
 
         fn generate_key_ownership_proof(
             _set_id: fg_primitives::SetId,
-            _authority_id: GrandpaId,
+            authority_id: GrandpaId,
         ) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-            None
+            Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+                .map(|proof| proof.encode())
+                .map(fg_primitives::OpaqueKeyOwnershipProof::new)
+        }
+    }
+
+    impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance>
+        for Runtime
+    {
+        fn query_info(
+            uxt: <Block as BlockT>::Extrinsic,
+            len: u32,
+        ) -> pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo<Balance> {
+            TransactionPayment::query_info(uxt, len)
+        }
+    }
+
+    impl sunshine_escrow_rpc_runtime_api::BountyApi<
+        Block,
+        AccountId,
+        Cid,
+        EscrowId,
+        SubmissionId,
+        Uid,
+        Balance,
+        BlockNumber,
+    > for Runtime {
+        fn open_bounties(
+            min_total: Balance,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(EscrowId, sunshine_escrow_pallet::EscrowBounty<AccountId, Cid, Balance, BlockNumber>)> {
+            Escrow::open_bounties(min_total, offset, limit)
+        }
+
+        fn submissions(
+            id: EscrowId,
+        ) -> Vec<(SubmissionId, sunshine_escrow_pallet::MilestoneSubmission<AccountId, Cid>)> {
+            Escrow::bounty_submissions(id)
+        }
+
+        fn contributions(account: AccountId) -> Vec<(EscrowId, Balance)> {
+            Escrow::account_contributions(&account)
+        }
+
+        fn pending_approvals(uid: Uid) -> Vec<(EscrowId, SubmissionId, Vec<Uid>)> {
+            Escrow::pending_approvals(uid)
+        }
+
+        fn vesting_balance(account: AccountId) -> sunshine_escrow_pallet::VestingBalance<Balance> {
+            Escrow::vesting_balance(&account)
+        }
+    }
+
+    impl sunshine_identity_rpc_runtime_api::IdentityApi<Block, AccountId, Uid, Cid> for Runtime {
+        fn uid(account: AccountId) -> Option<Uid> {
+            Identity::uid_lookup(account)
+        }
+
+        fn devices(uid: Uid) -> Vec<AccountId> {
+            Identity::devices(uid)
+        }
+
+        fn identity(uid: Uid) -> Option<Cid> {
+            Identity::identity(uid)
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    impl frame_benchmarking::Benchmark<Block> for Runtime {
+        fn dispatch_benchmark(
+            config: frame_benchmarking::BenchmarkConfig,
+        ) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+            use crate::benchmarks::bounty::Module as BountyBench;
+            use crate::benchmarks::identity::Module as IdentityBench;
+            use frame_benchmarking::{add_benchmark, BenchmarkBatch, Benchmarking};
+
+            impl crate::benchmarks::bounty::Trait for Runtime {}
+            impl crate::benchmarks::identity::Trait for Runtime {}
+
+            let whitelist: Vec<Vec<u8>> = vec![
+                // Block Number
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec(),
+                // Total Issuance
+                hex_literal::hex!("c2261276cc9d1f8598ea4b6a74b15c2f57c875e4cff74148e4628f264b974c80").to_vec(),
+                // Execution Phase
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec(),
+                // Event Count
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec(),
+                // System Events
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec(),
+            ];
+
+            let mut batches = Vec::<BenchmarkBatch>::new();
+            let params = (&config, &whitelist);
+
+            add_benchmark!(params, batches, pallet_balances, Balances);
+            add_benchmark!(params, batches, pallet_collective, Council);
+            add_benchmark!(params, batches, pallet_democracy, Democracy);
+            add_benchmark!(params, batches, pallet_proxy, Proxy);
+            add_benchmark!(params, batches, pallet_scheduler, Scheduler);
+            add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+            add_benchmark!(params, batches, pallet_treasury, Treasury);
+            add_benchmark!(params, batches, pallet_utility, Utility);
+            add_benchmark!(params, batches, sunshine_bounty, BountyBench::<Runtime>);
+            add_benchmark!(params, batches, sunshine_escrow_pallet, Escrow);
+            add_benchmark!(params, batches, sunshine_faucet_pallet, Faucet);
+            add_benchmark!(params, batches, sunshine_identity_pallet, IdentityBench::<Runtime>);
+
+            if batches.is_empty() {
+                return Err("Benchmark not found for this pallet.".into());
+            }
+            Ok(batches)
         }
     }
 }
@@ -0,0 +1,293 @@
+//! Storage migrations run by `Executive` when the runtime is upgraded.
+//!
+//! The spec version the storage was last migrated to is kept in storage. An
+//! upgrade runs every migration introduced after that version in order, so a
+//! chain can skip runtime versions. The genesis of a chain records the spec
+//! version it was built with, since it already has the current layout.
+//! Chains that predate the stored version are assumed to be at spec
+//! version 1.
+use crate::{
+    opaque::SessionKeys, AccountId, ArbiterMembership, Aura, CouncilMembership, Grandpa,
+    MinContribution, MinDeposit, MintUnit, Origin, Runtime, VERSION,
+};
+use frame_support::storage::unhashed;
+use frame_support::traits::{Get, OnRuntimeUpgrade, UnfilteredDispatchable};
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use frame_support::{debug, decl_module, decl_storage};
+use sp_core::crypto::Public;
+use sp_core::sr25519;
+use sp_std::prelude::*;
+
+/// Storage key of the spec version the storage was last migrated to.
+const SPEC_VERSION_KEY: &[u8] = b":sunshine:spec_version:";
+
+/// Migrations keyed on the spec version that introduced them, in order.
+const MIGRATIONS: &[(u32, fn() -> Weight)] =
+    &[(2, v2::migrate), (4, v4::migrate), (15, v15::migrate)];
+
+pub trait Trait: frame_system::Trait {}
+
+decl_module! {
+    /// Records the spec version of the genesis, so that the first upgrade
+    /// of a new chain doesn't run the migrations of older versions.
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as StorageVersion {}
+    add_extra_genesis {
+        build(|_config: &GenesisConfig| {
+            unhashed::put(SPEC_VERSION_KEY, &VERSION.spec_version);
+        });
+    }
+}
+
+pub struct Migrations;
+
+impl OnRuntimeUpgrade for Migrations {
+    fn on_runtime_upgrade() -> Weight {
+        debug::RuntimeLogger::init();
+        let from = unhashed::get(SPEC_VERSION_KEY).unwrap_or(1);
+        let weight = MIGRATIONS
+            .iter()
+            .filter(|(version, _)| *version > from)
+            .fold(
+                RocksDbWeight::get().reads_writes(1, 1),
+                |weight, (_, migrate)| weight.saturating_add(migrate()),
+            );
+        unhashed::put(SPEC_VERSION_KEY, &VERSION.spec_version);
+        weight
+    }
+}
+
+/// Spec version 2 added governance and escrow disputes.
+mod v2 {
+    use super::*;
+
+    /// The council and the arbiters start out empty on chains that were
+    /// created before they existed. Both are initialized with the aura
+    /// authorities, like in the genesis of new chains.
+    pub fn migrate() -> Weight {
+        let authorities = Aura::authorities()
+            .iter()
+            .map(|id| AccountId::from(sr25519::Public::from_slice(id.as_slice())))
+            .collect::<Vec<_>>();
+        let mut writes = 0;
+        if CouncilMembership::members().is_empty() {
+            if let Err(err) = CouncilMembership::reset_members(Origin::root(), authorities.clone())
+            {
+                debug::error!("failed to initialize the council: {:?}", err);
+            }
+            writes += 3;
+        }
+        if ArbiterMembership::members().is_empty() {
+            if let Err(err) = ArbiterMembership::reset_members(Origin::root(), authorities) {
+                debug::error!("failed to initialize the arbiters: {:?}", err);
+            }
+            writes += 1;
+        }
+        RocksDbWeight::get().reads_writes(3, writes)
+    }
+}
+
+/// Spec version 4 added sessions and the validator set.
+mod v4 {
+    use super::*;
+
+    /// The aura and grandpa authorities of existing chains become the
+    /// validators of the first session, with the account of their aura key
+    /// as the validator account.
+    pub fn migrate() -> Weight {
+        if !pallet_session::Module::<Runtime>::validators().is_empty() {
+            return RocksDbWeight::get().reads(1);
+        }
+        let keys = Aura::authorities()
+            .into_iter()
+            .zip(Grandpa::grandpa_authorities())
+            .map(|(aura, (grandpa, _))| {
+                let account = AccountId::from(sr25519::Public::from_slice(aura.as_slice()));
+                (account, SessionKeys { aura, grandpa })
+            })
+            .collect::<Vec<_>>();
+        for (account, keys) in &keys {
+            if let Err(err) = pallet_session::Call::<Runtime>::set_keys(keys.clone(), vec![])
+                .dispatch_bypass_filter(Origin::signed(account.clone()))
+            {
+                debug::error!("failed to set the session keys of {:?}: {:?}", account, err);
+            }
+        }
+        let validators = keys
+            .iter()
+            .map(|(account, _)| account.clone())
+            .collect::<Vec<_>>();
+        let count = keys.len() as Weight;
+        pallet_session::Validators::<Runtime>::put(&validators);
+        pallet_session::QueuedKeys::<Runtime>::put(keys);
+        sunshine_validator_set_pallet::Validators::<Runtime>::put(validators);
+        RocksDbWeight::get().reads_writes(3 + 2 * count, 3 + 4 * count)
+    }
+}
+
+/// Spec version 15 moved the deposits of the bounty pallet and the mint unit
+/// of the faucet into governed storage.
+///
+/// The identity pallet didn't change its layout since spec version 1 and has
+/// nothing to migrate.
+mod v15 {
+    use super::*;
+
+    /// Writes the values the parameters had as constants, so they can be
+    /// read from storage and changed with `system.setStorage`. Values that
+    /// were already set by governance are kept.
+    pub fn migrate() -> Weight {
+        let mut writes = 0;
+        if !unhashed::exists(&MinDeposit::key()) {
+            MinDeposit::set(&MinDeposit::get());
+            writes += 1;
+        }
+        if !unhashed::exists(&MinContribution::key()) {
+            MinContribution::set(&MinContribution::get());
+            writes += 1;
+        }
+        if !unhashed::exists(&MintUnit::key()) {
+            MintUnit::set(&MintUnit::get());
+            writes += 1;
+        }
+        RocksDbWeight::get().reads_writes(3, writes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuraId, Balances, GrandpaId, Session, ValidatorSet};
+    use sp_core::ed25519;
+    use sp_runtime::BuildStorage;
+
+    /// Aura and grandpa keys of the authorities of an old chain.
+    fn authorities() -> Vec<(AuraId, GrandpaId)> {
+        (1..=2)
+            .map(|i| {
+                (
+                    AuraId::from(sr25519::Public::from_raw([i; 32])),
+                    GrandpaId::from(ed25519::Public::from_raw([i; 32])),
+                )
+            })
+            .collect()
+    }
+
+    fn account(i: u8) -> AccountId {
+        AccountId::from(sr25519::Public::from_raw([i; 32]))
+    }
+
+    /// Storage of a chain built at spec version 1, which only had balances,
+    /// aura and grandpa.
+    fn old_genesis() -> sp_io::TestExternalities {
+        let mut storage = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(account(1), 1 << 60), (account(2), 1 << 60)],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        pallet_aura::GenesisConfig::<Runtime> {
+            authorities: authorities().into_iter().map(|(aura, _)| aura).collect(),
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        pallet_grandpa::GenesisConfig {
+            authorities: authorities()
+                .into_iter()
+                .map(|(_, grandpa)| (grandpa, 1))
+                .collect(),
+        }
+        .assimilate_storage::<Runtime>(&mut storage)
+        .unwrap();
+        sp_io::TestExternalities::new(storage)
+    }
+
+    /// Storage of a chain built with the current runtime.
+    fn new_genesis() -> sp_io::TestExternalities {
+        let mut storage = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+        GenesisConfig::default()
+            .assimilate_storage::<Runtime>(&mut storage)
+            .unwrap();
+        sp_io::TestExternalities::new(storage)
+    }
+
+    #[test]
+    fn genesis_records_spec_version() {
+        new_genesis().execute_with(|| {
+            assert_eq!(
+                unhashed::get::<u32>(SPEC_VERSION_KEY),
+                Some(VERSION.spec_version)
+            );
+        });
+    }
+
+    #[test]
+    fn upgrade_of_new_chain_skips_old_migrations() {
+        new_genesis().execute_with(|| {
+            Migrations::on_runtime_upgrade();
+            // The council and arbiters are left alone even though they are
+            // empty, governance may have emptied them.
+            assert!(CouncilMembership::members().is_empty());
+            assert!(ArbiterMembership::members().is_empty());
+            assert!(!unhashed::exists(&MintUnit::key()));
+        });
+    }
+
+    #[test]
+    fn upgrade_of_old_chain_migrates_layout() {
+        old_genesis().execute_with(|| {
+            let accounts = vec![account(1), account(2)];
+            assert_eq!(unhashed::get::<u32>(SPEC_VERSION_KEY), None);
+            assert!(Session::validators().is_empty());
+
+            Migrations::on_runtime_upgrade();
+
+            assert_eq!(
+                unhashed::get::<u32>(SPEC_VERSION_KEY),
+                Some(VERSION.spec_version)
+            );
+            // v2
+            assert_eq!(CouncilMembership::members(), accounts);
+            assert_eq!(ArbiterMembership::members(), accounts);
+            // v4
+            assert_eq!(Session::validators(), accounts);
+            assert_eq!(ValidatorSet::validators(), accounts);
+            assert_eq!(Session::queued_keys().len(), 2);
+            // v15
+            assert_eq!(unhashed::get::<u128>(&MinDeposit::key()), Some(10));
+            assert_eq!(unhashed::get::<u128>(&MinContribution::key()), Some(5));
+            assert_eq!(unhashed::get::<u128>(&MintUnit::key()), Some(1_000_000_000));
+            // The identity pallet stores the balances and is left alone.
+            assert_eq!(Balances::free_balance(account(1)), 1 << 60);
+        });
+    }
+
+    #[test]
+    fn migrations_run_once() {
+        old_genesis().execute_with(|| {
+            Migrations::on_runtime_upgrade();
+            CouncilMembership::reset_members(Origin::root(), vec![]).unwrap();
+            MintUnit::set(&1);
+
+            Migrations::on_runtime_upgrade();
+            assert!(CouncilMembership::members().is_empty());
+            assert_eq!(MintUnit::get(), 1);
+        });
+    }
+
+    #[test]
+    fn governed_parameters_are_kept() {
+        old_genesis().execute_with(|| {
+            MinDeposit::set(&100);
+            Migrations::on_runtime_upgrade();
+            assert_eq!(MinDeposit::get(), 100);
+        });
+    }
+}
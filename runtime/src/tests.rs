@@ -0,0 +1,130 @@
+//! Tests of the interplay of the pallets of the runtime.
+use crate::opaque::SessionKeys;
+use crate::{
+    AccountId, AuraId, Event, Grandpa, GrandpaId, Historical, Origin, Runtime, Session, System,
+    ValidatorSet,
+};
+use frame_support::assert_ok;
+use frame_support::traits::KeyOwnerProofSystem;
+use pallet_grandpa::fg_primitives::{self, Equivocation, EquivocationProof};
+use sp_core::{Pair, H256};
+use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+use sp_runtime::BuildStorage;
+use sunshine_validator_set_pallet::RawEvent;
+
+const VALIDATORS: &[(Sr25519Keyring, Ed25519Keyring)] = &[
+    (Sr25519Keyring::Alice, Ed25519Keyring::Alice),
+    (Sr25519Keyring::Bob, Ed25519Keyring::Bob),
+];
+
+/// Storage of a chain with the validators of `VALIDATORS`, in its first session.
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Runtime>()
+        .unwrap();
+    let validators = VALIDATORS
+        .iter()
+        .map(|(account, _)| account.to_account_id())
+        .collect::<Vec<_>>();
+    sunshine_validator_set_pallet::GenesisConfig::<Runtime> {
+        validators: validators.clone(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    // The aura and grandpa authorities are initialized by the session.
+    pallet_session::GenesisConfig::<Runtime> {
+        keys: VALIDATORS
+            .iter()
+            .map(|(account, grandpa)| {
+                let keys = SessionKeys {
+                    aura: AuraId::from(account.public()),
+                    grandpa: GrandpaId::from(grandpa.public()),
+                };
+                (account.to_account_id(), account.to_account_id(), keys)
+            })
+            .collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Two prevotes of `offender` for different blocks in the same round, signed
+/// by `signer`.
+fn double_vote(offender: Ed25519Keyring, signer: Ed25519Keyring) -> EquivocationProof<H256, u32> {
+    let set_id = Grandpa::current_set_id();
+    let round = 1;
+    let prevote = |target_hash| {
+        let prevote = finality_grandpa::Prevote {
+            target_hash,
+            target_number: 1,
+        };
+        let message = finality_grandpa::Message::Prevote(prevote.clone());
+        let payload = fg_primitives::localized_payload(round, set_id, &message);
+        (prevote, signer.pair().sign(&payload).into())
+    };
+    EquivocationProof::new(
+        set_id,
+        Equivocation::Prevote(finality_grandpa::Equivocation {
+            round_number: round,
+            identity: GrandpaId::from(offender.public()),
+            first: prevote(H256::repeat_byte(1)),
+            second: prevote(H256::repeat_byte(2)),
+        }),
+    )
+}
+
+#[test]
+fn grandpa_double_vote_removes_validator() {
+    new_test_ext().execute_with(|| {
+        let (account, grandpa) = VALIDATORS[0];
+        let offender: AccountId = account.to_account_id();
+        let key_owner_proof =
+            Historical::prove((fg_primitives::KEY_TYPE, GrandpaId::from(grandpa.public())))
+                .unwrap();
+
+        assert_ok!(Grandpa::report_equivocation_unsigned(
+            Origin::none(),
+            double_vote(grandpa, grandpa),
+            key_owner_proof.clone(),
+        ));
+
+        assert!(!ValidatorSet::validators().contains(&offender));
+        assert!(System::events().iter().any(|record| matches!(
+            &record.event,
+            Event::sunshine_validator_set_pallet(RawEvent::ValidatorOffence(validator, _, true))
+                if *validator == offender
+        )));
+        // The removal takes effect two sessions later, the offender isn't
+        // slashed and keeps validating until then.
+        assert!(Session::validators().contains(&offender));
+
+        // The offence of the same round is only reported once.
+        assert!(Grandpa::report_equivocation_unsigned(
+            Origin::none(),
+            double_vote(grandpa, grandpa),
+            key_owner_proof,
+        )
+        .is_err());
+    });
+}
+
+#[test]
+fn forged_double_vote_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let (_, grandpa) = VALIDATORS[0];
+        let key_owner_proof =
+            Historical::prove((fg_primitives::KEY_TYPE, GrandpaId::from(grandpa.public())))
+                .unwrap();
+        // Votes signed by another validator don't prove an equivocation of
+        // the reported one.
+        let proof = double_vote(grandpa, VALIDATORS[1].1);
+
+        assert!(
+            Grandpa::report_equivocation_unsigned(Origin::none(), proof, key_owner_proof).is_err()
+        );
+        assert_eq!(ValidatorSet::validators().len(), VALIDATORS.len());
+    });
+}
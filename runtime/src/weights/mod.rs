@@ -0,0 +1,9 @@
+//! Weights of the sunshine pallets for this runtime.
+//!
+//! The modules have the layout of the output of `sunshine-node benchmark
+//! --pallet <pallet> --extrinsic '*' --output` and are overwritten by it, see
+//! the README for the full command. Until they are generated on the reference
+//! hardware they carry the estimates of the pallets, charged with the
+//! database weights of the runtime.
+pub mod sunshine_escrow_pallet;
+pub mod sunshine_faucet_pallet;
@@ -0,0 +1,16 @@
+//! Weights for sunshine_faucet_pallet
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::traits::Get;
+use frame_support::weights::Weight;
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Trait> sunshine_faucet_pallet::WeightInfo for WeightInfo<T> {
+    fn mint() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+}